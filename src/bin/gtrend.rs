@@ -0,0 +1,389 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures_util::stream::{Stream, StreamExt};
+use gtrend::developers::Developer;
+#[cfg(feature = "arrow")]
+use gtrend::export::arrow;
+use gtrend::export::csv::{self, DeveloperColumn, RepositoryColumn};
+use gtrend::export::feed::Feed;
+use gtrend::export::{html, markdown, ndjson};
+use gtrend::notify::{self, Notifier, Webhook};
+use gtrend::repos::Repository;
+use gtrend::watch::{self, Event};
+use gtrend::{
+    developers, languages, periods, repos, spoken_languages, stats, Error, Schema, Since,
+    TrendingQuery,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::io;
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+// per request, so a hung connection does not stall a command forever
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Parser)]
+#[command(name = "gtrend", version, about = "Scrape github.com/trending")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Trending repositories
+    Repos {
+        #[arg(short, long)]
+        language: Option<String>,
+        #[arg(short, long)]
+        spoken_language: Option<String>,
+        #[arg(long)]
+        since: Option<Since>,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Trending developers
+    Developers {
+        #[arg(short, long)]
+        language: Option<String>,
+        #[arg(long)]
+        since: Option<Since>,
+        /// Only developers that can be sponsored
+        #[arg(long)]
+        sponsorable: bool,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Trending repositories or developers of a github.com/trending link
+    Url {
+        url: String,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Poll trending repositories or developers and print changes as ndjson
+    Watch {
+        /// Watch developers instead of repositories
+        #[arg(long)]
+        developers: bool,
+        #[arg(short, long)]
+        language: Option<String>,
+        #[arg(short, long)]
+        spoken_language: Option<String>,
+        #[arg(long)]
+        since: Option<Since>,
+        /// Seconds between two polls
+        #[arg(long, default_value_t = 300)]
+        interval: u64,
+        /// Print every row of the first poll as entered
+        #[arg(long)]
+        emit_initial: bool,
+        /// POST new entries to this url, can be repeated
+        #[arg(long)]
+        webhook: Vec<String>,
+        /// json, slack, discord or teams
+        #[arg(long, default_value = "json")]
+        webhook_format: notify::Format,
+        /// Sign webhook bodies with HMAC-SHA256
+        #[arg(long)]
+        webhook_secret: Option<String>,
+    },
+    /// Language, author and contributor counts of trending repositories
+    Stats {
+        #[arg(short, long)]
+        language: Option<String>,
+        #[arg(short, long)]
+        spoken_language: Option<String>,
+        #[arg(long)]
+        since: Option<Since>,
+    },
+    /// Repositories trending daily only (flash), daily and weekly (rising)
+    /// or in all three periods (sustained)
+    Periods {
+        #[arg(short, long)]
+        language: Option<String>,
+        #[arg(short, long)]
+        spoken_language: Option<String>,
+    },
+    /// Available programming languages
+    Languages,
+    /// Available spoken languages
+    SpokenLanguages,
+    /// Serve the JSON API over HTTP
+    #[cfg(feature = "server")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8000")]
+        addr: SocketAddr,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Ndjson,
+    Csv,
+    Markdown,
+    Html,
+    Rss,
+    Atom,
+    /// Binary, redirect it to a file
+    #[cfg(feature = "arrow")]
+    Parquet,
+}
+
+#[derive(Debug, Args)]
+struct Output {
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// Field names of json and ndjson: v1-snake or github-trending-api-compat
+    #[arg(long, default_value_t = Schema::V1Snake)]
+    schema: Schema,
+    /// Comma separated csv columns, e.g. `full_name,current_star,built_by`
+    #[arg(long, value_delimiter = ',')]
+    columns: Vec<String>,
+}
+
+impl Output {
+    fn repos(&self, query: &TrendingQuery, data: &[Repository]) -> Result<(), Error> {
+        match self.format {
+            Format::Json => print_json(&self.schema.repos_json(data)),
+            Format::Ndjson => print_ndjson(data.iter().map(|x| self.schema.repository(x))),
+            Format::Csv => {
+                let columns: Vec<RepositoryColumn> = parse_columns(&self.columns)?;
+                csv::write_repos(io::stdout(), data, &columns)
+            }
+            Format::Markdown => print(markdown::render_repos(data, &markdown::repos_table())),
+            Format::Html => print(html::render_repos(data, &html::repos_page())),
+            Format::Rss => print(Feed::new(query).repos_rss(data)),
+            Format::Atom => print(Feed::new(query).repos_atom(data)),
+            #[cfg(feature = "arrow")]
+            Format::Parquet => arrow::write_repos(io::stdout(), data),
+        }
+    }
+
+    fn developers(&self, query: &TrendingQuery, data: &[Developer]) -> Result<(), Error> {
+        match self.format {
+            Format::Json => print_json(&self.schema.developers_json(data)),
+            Format::Ndjson => print_ndjson(data.iter().map(|x| self.schema.developer(x))),
+            Format::Csv => {
+                let columns: Vec<DeveloperColumn> = parse_columns(&self.columns)?;
+                csv::write_developers(io::stdout(), data, &columns)
+            }
+            Format::Markdown => print(markdown::render_developers(
+                data,
+                &markdown::developers_table(),
+            )),
+            Format::Html => print(html::render_developers(data, &html::developers_page())),
+            Format::Rss => print(Feed::new(query).developers_rss(data)),
+            Format::Atom => print(Feed::new(query).developers_atom(data)),
+            #[cfg(feature = "arrow")]
+            Format::Parquet => arrow::write_developers(io::stdout(), data),
+        }
+    }
+}
+
+fn parse_columns<C: FromStr<Err = Error>>(columns: &[String]) -> Result<Vec<C>, Error> {
+    columns.iter().map(|x| x.parse()).collect()
+}
+
+fn print(out: String) -> Result<(), Error> {
+    print!("{}", out);
+    Ok(())
+}
+
+trait Notify: Serialize + Sized {
+    async fn notify(notifier: &Notifier, events: &[Event<Self>]) -> Result<(), Error>;
+}
+
+impl Notify for Repository {
+    async fn notify(notifier: &Notifier, events: &[Event<Self>]) -> Result<(), Error> {
+        notifier.notify_repos(events).await
+    }
+}
+
+impl Notify for Developer {
+    async fn notify(notifier: &Notifier, events: &[Event<Self>]) -> Result<(), Error> {
+        notifier.notify_developers(events).await
+    }
+}
+
+// runs until interrupted; failed polls and webhooks are reported and retried
+async fn print_events<T, S>(events: S, notifier: &Notifier) -> Result<(), Error>
+where
+    T: Notify,
+    S: Stream<Item = Result<Event<T>, Error>>,
+{
+    futures_util::pin_mut!(events);
+    let mut wtr = ndjson::Writer::new(io::stdout());
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(event) => {
+                wtr.write(&event)?;
+                wtr.flush()?;
+                if let Err(e) = T::notify(notifier, &[event]).await {
+                    eprintln!("error: {}", e);
+                }
+            }
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn print_ndjson<I: Iterator<Item = Value>>(records: I) -> Result<(), Error> {
+    let mut wtr = ndjson::Writer::new(io::stdout());
+    for x in records {
+        wtr.write(&x)?;
+    }
+    wtr.flush()
+}
+
+fn print_json<T: Serialize + ?Sized>(data: &T) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(data)?;
+    println!("{}", json);
+    Ok(())
+}
+
+async fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Repos {
+            language,
+            spoken_language,
+            since,
+            output,
+        } => {
+            let mut builder = repos::builder().timeout(TIMEOUT);
+            if let Some(lang) = language {
+                builder = builder.programming_language(lang);
+            }
+            if let Some(s_lang) = spoken_language {
+                builder = builder.spoken_language(s_lang);
+            }
+            if let Some(since) = since {
+                builder = builder.since(since);
+            }
+            output.repos(builder.query(), &builder.get_data().await?)
+        }
+        Command::Developers {
+            language,
+            since,
+            sponsorable,
+            output,
+        } => {
+            let mut builder = developers::builder()
+                .timeout(TIMEOUT)
+                .sponsorable(sponsorable);
+            if let Some(lang) = language {
+                builder = builder.programming_language(lang);
+            }
+            if let Some(since) = since {
+                builder = builder.since(since);
+            }
+            let query = builder.query().clone();
+            output.developers(&query, &builder.get_data().await?)
+        }
+        Command::Url { url, output } => match repos::Builder::from_url(&url) {
+            Ok(builder) => {
+                let builder = builder.timeout(TIMEOUT);
+                output.repos(builder.query(), &builder.get_data().await?)
+            }
+            Err(_) => {
+                let builder = developers::Builder::from_url(&url)?.timeout(TIMEOUT);
+                let query = builder.query().clone();
+                output.developers(&query, &builder.get_data().await?)
+            }
+        },
+        Command::Watch {
+            developers,
+            language,
+            spoken_language,
+            since,
+            interval,
+            emit_initial,
+            webhook,
+            webhook_format,
+            webhook_secret,
+        } => {
+            let interval = Duration::from_secs(interval);
+            let mut notifier = Notifier::new();
+            for url in webhook {
+                let mut hook = Webhook::new(url).format(webhook_format);
+                if let Some(secret) = webhook_secret.as_ref() {
+                    hook = hook.secret(secret);
+                }
+                notifier = notifier.webhook(hook);
+            }
+
+            if developers {
+                let mut builder = developers::builder().timeout(TIMEOUT);
+                if let Some(lang) = language {
+                    builder = builder.programming_language(lang);
+                }
+                if let Some(since) = since {
+                    builder = builder.since(since);
+                }
+                let watcher = watch::developers(builder).interval(interval);
+                print_events(watcher.emit_initial(emit_initial).stream(), &notifier).await
+            } else {
+                let mut builder = repos::builder().timeout(TIMEOUT);
+                if let Some(lang) = language {
+                    builder = builder.programming_language(lang);
+                }
+                if let Some(s_lang) = spoken_language {
+                    builder = builder.spoken_language(s_lang);
+                }
+                if let Some(since) = since {
+                    builder = builder.since(since);
+                }
+                let watcher = watch::repos(builder).interval(interval);
+                print_events(watcher.emit_initial(emit_initial).stream(), &notifier).await
+            }
+        }
+        Command::Stats {
+            language,
+            spoken_language,
+            since,
+        } => {
+            let mut builder = repos::builder().timeout(TIMEOUT);
+            if let Some(lang) = language {
+                builder = builder.programming_language(lang);
+            }
+            if let Some(s_lang) = spoken_language {
+                builder = builder.spoken_language(s_lang);
+            }
+            if let Some(since) = since {
+                builder = builder.since(since);
+            }
+            print_json(&stats::summarize(&builder.get_data().await?))
+        }
+        Command::Periods {
+            language,
+            spoken_language,
+        } => {
+            let mut builder = repos::builder().timeout(TIMEOUT);
+            if let Some(lang) = language {
+                builder = builder.programming_language(lang);
+            }
+            if let Some(s_lang) = spoken_language {
+                builder = builder.spoken_language(s_lang);
+            }
+            print_json(&periods::compare(&builder).await?)
+        }
+        Command::Languages => print_json(&languages::get_data_json()),
+        Command::SpokenLanguages => print_json(&spoken_languages::get_data_json()),
+        #[cfg(feature = "server")]
+        Command::Serve { addr } => gtrend::server::serve(addr).await,
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli.command).await {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,38 @@
+//! Synchronous versions of the repos and developers builders, for callers
+//! that do not run an async runtime. Enabled by the `blocking` feature.
+//!
+//! ```no_run
+//! use gtrend::blocking::repos;
+//! use gtrend::Since;
+//!
+//! let data = repos::builder()
+//!     .programming_language("rust")
+//!     .since(Since::Weekly)
+//!     .get_data();
+//! ```
+
+pub mod developers;
+pub mod repos;
+
+use crate::error::Error;
+use crate::FetchOptions;
+use std::time::{Duration, Instant};
+
+pub(crate) fn fetch_html(url: &str, opts: &FetchOptions) -> Result<String, Error> {
+    let mut timeout = opts.timeout;
+    if let Some(deadline) = opts.deadline {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::ZERO {
+            return Err(Error::Timeout);
+        }
+        timeout = Some(timeout.map_or(remaining, |x| x.min(remaining)));
+    }
+
+    let mut req = reqwest::blocking::Client::new().get(url);
+    if let Some(timeout) = timeout {
+        req = req.timeout(timeout);
+    }
+
+    let resp = req.send()?.text()?;
+    Ok(resp)
+}
@@ -0,0 +1,71 @@
+use crate::blocking::fetch_html;
+use crate::error::Error;
+use crate::repos::{self, Repository};
+use crate::{Schema, Since};
+use serde_json::Value;
+use std::borrow::Borrow;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct Builder {
+    inner: repos::Builder,
+}
+
+impl Builder {
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        repos::Builder::from_url(url).map(|inner| Builder { inner })
+    }
+
+    pub fn programming_language<T: Borrow<str>>(self, lang: T) -> Self {
+        Builder {
+            inner: self.inner.programming_language(lang),
+        }
+    }
+
+    pub fn since(self, since: Since) -> Self {
+        Builder {
+            inner: self.inner.since(since),
+        }
+    }
+
+    pub fn spoken_language<T: Borrow<str>>(self, s_lang: T) -> Self {
+        Builder {
+            inner: self.inner.spoken_language(s_lang),
+        }
+    }
+
+    pub fn schema(self, schema: Schema) -> Self {
+        Builder {
+            inner: self.inner.schema(schema),
+        }
+    }
+
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Builder {
+            inner: self.inner.timeout(timeout),
+        }
+    }
+
+    pub fn deadline(self, deadline: Instant) -> Self {
+        Builder {
+            inner: self.inner.deadline(deadline),
+        }
+    }
+
+    pub fn get_data_json(&self) -> Result<Value, Error> {
+        let schema = self.inner.schema;
+        let data = self.get_data()?;
+
+        Ok(schema.repos_json(&data))
+    }
+
+    pub fn get_data(&self) -> Result<Vec<Repository>, Error> {
+        fetch_html(&self.inner.url(), &self.inner.fetch).map(|x| repos::parse(&x))
+    }
+}
+
+pub fn builder() -> Builder {
+    Builder {
+        inner: repos::builder(),
+    }
+}
@@ -1,16 +1,27 @@
+#[cfg(any(feature = "async", feature = "blocking"))]
 use crate::error::Error;
+#[cfg(feature = "parse")]
 use crate::*;
+#[cfg(feature = "parse")]
 use select::document::Document;
+#[cfg(feature = "parse")]
 use select::predicate::{Class, Name};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+#[cfg(feature = "async")]
+use serde_json::Value;
+#[cfg(any(feature = "async", feature = "blocking"))]
 use std::borrow::Borrow;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Repo {
     pub name: Option<String>,
     pub description: Option<String>,
     pub url: Option<String>,
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +32,9 @@ pub struct Developer {
     pub sponsor_url: Option<String>,
     pub avatar: Option<String>,
     pub repo: Option<Repo>,
+    pub organization: Option<String>,
+    #[serde(default)]
+    pub sponsorable: bool,
 }
 
 impl Developer {
@@ -29,56 +43,117 @@ impl Developer {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
 #[derive(Debug, Clone)]
 pub struct Builder {
-    pro_lang: Option<String>,
-    since: Option<String>,
+    query: TrendingQuery,
+    pub(crate) fetch: FetchOptions,
+    pub(crate) schema: Schema,
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
 impl Builder {
+    /// Configure a builder from a pasted link such as
+    /// `https://github.com/trending/developers/rust?since=weekly&sponsorable=1`.
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let trending = TrendingQuery::from_url(url)?;
+        if trending.kind != query::Kind::Developers {
+            return Err(Error::InvalidUrl(url.to_string()));
+        }
+
+        let mut builder = builder();
+        if let Some(lang) = trending.language.as_ref() {
+            builder = builder.programming_language(lang.as_str());
+        }
+        if let Some(since) = trending.since {
+            builder = builder.since(since);
+        }
+        builder = builder.sponsorable(trending.sponsorable);
+
+        Ok(builder)
+    }
+
     pub fn programming_language<T: Borrow<str>>(mut self, lang: T) -> Self {
         let lang = lang.borrow();
         let lang_: Option<Language> = languages::find(By::Both(lang));
 
         match lang_ {
             Some(val) => {
-                self.pro_lang = Some(val.url_param);
+                self.query.language = Some(query::decode(&val.url_param));
                 self
             }
             _ => {
-                self.pro_lang = Some(lang.to_string());
+                self.query.language = Some(lang.to_string());
                 self
             }
         }
     }
 
     pub fn since(mut self, since: Since) -> Self {
-        let s: String = since.to_string();
-        self.since = Some(s);
+        self.query.since = Some(since);
+        self
+    }
+
+    /// Only list developers that can be sponsored.
+    pub fn sponsorable(mut self, sponsorable: bool) -> Self {
+        self.query.sponsorable = sponsorable;
+        self
+    }
+
+    /// Shape of the records returned by `get_data_json`.
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Limit for a single HTTP request to GitHub.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.fetch.timeout(timeout);
+        self
+    }
+
+    /// Point in time after which fetching fails with `Error::Timeout`.
+    /// Share one deadline between builders to bound a whole fan-out.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.fetch.deadline(deadline);
+        self
+    }
+
+    /// Abort the fetch with `Error::Cancelled` once `token` is cancelled.
+    #[cfg(feature = "async")]
+    pub fn cancel_token(mut self, token: CancellationToken) -> Self {
+        self.fetch.cancel_token(token);
         self
     }
 
+    #[cfg(feature = "async")]
     pub async fn get_data_json(self) -> Result<Value, Error> {
+        let schema = self.schema;
         let data = self.get_data().await?;
-        let data_json: Vec<Value> = data.into_iter().map(|x| json!(x)).collect();
 
-        Ok(Value::Array(data_json))
+        Ok(schema.developers_json(&data))
     }
 
+    #[cfg(feature = "async")]
     pub async fn get_data(self) -> Result<Vec<Developer>, Error> {
-        let params_url: String = match (self.pro_lang, self.since) {
-            (Some(l), Some(s)) => format!("/{}?since={}", l, s),
-            (None, Some(s)) => format!("?since={}", s),
-            (Some(l), None) => format!("/{}", l),
-            _ => "".to_string(),
-        };
-
-        let url = format!("{}{}{}", GITHUB_TRENDING_URL, "/developers", params_url);
-        fetch_html(&url).await.and_then(|x| Ok(select_data(&x)))
+        fetch_html(&self.url(), &self.fetch)
+            .await
+            .map(|x| parse(&x))
+    }
+
+    pub fn query(&self) -> &TrendingQuery {
+        &self.query
+    }
+
+    pub(crate) fn url(&self) -> String {
+        self.query.to_url()
     }
 }
 
-fn select_data(html: &str) -> Vec<Developer> {
+/// Parse the rows of an already downloaded trending page.
+#[cfg(feature = "parse")]
+#[allow(clippy::useless_conversion, clippy::needless_return, clippy::len_zero)]
+pub fn parse(html: &str) -> Vec<Developer> {
     let document = Document::from(html);
 
     let data: Vec<Developer> = document
@@ -137,12 +212,27 @@ fn select_data(html: &str) -> Vec<Developer> {
                 .map(|x| format!("{}/{}", GITHUB_BASE_URL, x));
 
             let sponsor_url: Option<String> = node
-                .find(Class("mr-2"))
-                .next()
-                .and_then(|x| x.find(Name("a")).next())
-                .and_then(|x| x.attr("href"))
+                .find(Name("a"))
+                .filter_map(|x| x.attr("href"))
+                .find(|x| x.starts_with("/sponsors/"))
                 .map(|x| format!("{}{}", GITHUB_BASE_URL, x));
 
+            let sponsorable: bool = sponsor_url.is_some();
+
+            let organization: Option<String> = node
+                .find(Class("octicon-organization"))
+                .next()
+                .and_then(|x| x.parent())
+                .map(|x| escape(x.text()))
+                .filter(|x| !x.is_empty());
+
+            // "Popular repo" or whichever badge heads the repo card
+            let repo_label: Option<String> = node
+                .find(Class("text-uppercase"))
+                .next()
+                .map(|x| escape(x.text()))
+                .filter(|x| !x.is_empty());
+
             let repo_description: Option<String> =
                 node.find(Class("mt-1")).next().map(|x| escape(x.text()));
 
@@ -154,6 +244,7 @@ fn select_data(html: &str) -> Vec<Developer> {
                 name: Some(x),
                 description: repo_description.clone(),
                 url: repo_url.clone(),
+                label: repo_label.clone(),
             });
 
             // println!("x {:?}", repo);
@@ -165,6 +256,8 @@ fn select_data(html: &str) -> Vec<Developer> {
                 sponsor_url,
                 avatar,
                 repo,
+                organization,
+                sponsorable,
             };
         })
         .collect();
@@ -172,9 +265,11 @@ fn select_data(html: &str) -> Vec<Developer> {
     data
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
 pub fn builder() -> Builder {
     Builder {
-        pro_lang: None,
-        since: None,
+        query: TrendingQuery::developers(),
+        fetch: FetchOptions::default(),
+        schema: Schema::default(),
     }
 }
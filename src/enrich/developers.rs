@@ -0,0 +1,83 @@
+use super::Client;
+use crate::developers::Developer;
+use crate::error::Error;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserDetails {
+    pub followers: Option<u32>,
+    pub public_repos: Option<u32>,
+    pub company: Option<String>,
+    pub location: Option<String>,
+    pub bio: Option<String>,
+    pub blog: Option<String>,
+}
+
+/// `details` is `None` when the account is gone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichedDeveloper {
+    #[serde(flatten)]
+    pub developer: Developer,
+    pub details: Option<UserDetails>,
+}
+
+#[derive(Deserialize)]
+struct ApiUser {
+    followers: Option<u32>,
+    public_repos: Option<u32>,
+    company: Option<String>,
+    location: Option<String>,
+    bio: Option<String>,
+    blog: Option<String>,
+}
+
+impl From<ApiUser> for UserDetails {
+    fn from(x: ApiUser) -> Self {
+        let non_empty = |x: Option<String>| x.filter(|x| !x.trim().is_empty());
+
+        UserDetails {
+            followers: x.followers,
+            public_repos: x.public_repos,
+            company: non_empty(x.company),
+            location: non_empty(x.location),
+            bio: non_empty(x.bio),
+            blog: non_empty(x.blog),
+        }
+    }
+}
+
+impl Client {
+    /// `/users/{username}`, `None` when it does not exist.
+    pub async fn user_details(&self, username: &str) -> Result<Option<UserDetails>, Error> {
+        if let Some(details) = self.cached(&self.users, username) {
+            return Ok(details);
+        }
+
+        let details: Option<UserDetails> = self
+            .get::<ApiUser>(&format!("/users/{}", username))
+            .await?
+            .map(UserDetails::from);
+        self.cache(&self.users, username, details.clone());
+
+        Ok(details)
+    }
+
+    /// Look up every developer, `concurrency` at a time, keeping the order.
+    pub async fn enrich_developers(
+        &self,
+        data: Vec<Developer>,
+    ) -> Result<Vec<EnrichedDeveloper>, Error> {
+        stream::iter(data)
+            .map(|developer| async move {
+                let details = match developer.username.as_ref() {
+                    Some(username) => self.user_details(username).await?,
+                    None => None,
+                };
+                Ok(EnrichedDeveloper { developer, details })
+            })
+            .buffered(self.concurrency)
+            .try_collect()
+            .await
+    }
+}
@@ -0,0 +1,184 @@
+use super::{Client, EnrichedRepository, RepoDetails};
+use crate::error::Error;
+use crate::repos::Repository;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+// repositories per query, well below GitHub's node limit
+const BATCH: usize = 50;
+
+// `issues` leaves out pull requests, both add up to REST's `open_issues_count`
+const FIELDS: &str = "repositoryTopics(first: 20) { nodes { topic { name } } } \
+                      licenseInfo { spdxId name } createdAt issues(states: OPEN) { totalCount } \
+                      pullRequests(states: OPEN) { totalCount } \
+                      homepageUrl defaultBranchRef { name }";
+
+#[derive(Deserialize)]
+struct Name {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TopicNode {
+    topic: Name,
+}
+
+#[derive(Deserialize)]
+struct Topics {
+    nodes: Vec<TopicNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct License {
+    spdx_id: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Count {
+    total_count: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GqlRepo {
+    repository_topics: Topics,
+    license_info: Option<License>,
+    created_at: Option<String>,
+    issues: Count,
+    pull_requests: Count,
+    homepage_url: Option<String>,
+    default_branch_ref: Option<Name>,
+}
+
+#[derive(Deserialize)]
+struct GqlError {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    data: Option<HashMap<String, Option<GqlRepo>>>,
+    #[serde(default)]
+    errors: Vec<GqlError>,
+}
+
+impl From<GqlRepo> for RepoDetails {
+    fn from(x: GqlRepo) -> Self {
+        let license = x.license_info.and_then(|l| {
+            l.spdx_id
+                .filter(|id| !id.is_empty() && id != "NOASSERTION")
+                .or(l.name)
+        });
+
+        RepoDetails {
+            topics: x
+                .repository_topics
+                .nodes
+                .into_iter()
+                .map(|x| x.topic.name)
+                .collect(),
+            license,
+            created_at: x.created_at,
+            open_issues: Some(x.issues.total_count + x.pull_requests.total_count),
+            homepage: x.homepage_url.filter(|x| !x.is_empty()),
+            default_branch: x.default_branch_ref.map(|x| x.name),
+        }
+    }
+}
+
+/// `r0: repository(owner: $o0, name: $n0) { ... }` for every pair.
+fn query(pairs: &[(&str, &str)]) -> Value {
+    let mut params = Vec::new();
+    let mut fields = String::new();
+    let mut variables = Map::new();
+    for (i, (owner, name)) in pairs.iter().enumerate() {
+        params.push(format!("$o{i}: String!, $n{i}: String!", i = i));
+        fields.push_str(&format!(
+            "r{i}: repository(owner: $o{i}, name: $n{i}) {{ {fields} }} ",
+            i = i,
+            fields = FIELDS
+        ));
+        variables.insert(format!("o{}", i), json!(owner));
+        variables.insert(format!("n{}", i), json!(name));
+    }
+
+    json!({
+        "query": format!("query({}) {{ {}}}", params.join(", "), fields),
+        "variables": variables,
+    })
+}
+
+impl Client {
+    pub(super) async fn enrich_repos_graphql(
+        &self,
+        data: Vec<Repository>,
+    ) -> Result<Vec<EnrichedRepository>, Error> {
+        let key = |x: &Repository| -> Option<String> {
+            Some(format!("{}/{}", x.author.as_ref()?, x.name.as_ref()?))
+        };
+
+        let mut found: HashMap<String, Option<RepoDetails>> = HashMap::new();
+        let mut missing: Vec<(&str, &str)> = Vec::new();
+        for x in &data {
+            if let (Some(k), Some(author), Some(name)) =
+                (key(x), x.author.as_ref(), x.name.as_ref())
+            {
+                match self.cached(&self.repos, &k) {
+                    Some(details) => {
+                        found.insert(k, details);
+                    }
+                    None if !missing.contains(&(author.as_str(), name.as_str())) => {
+                        missing.push((author, name))
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        for pairs in missing.chunks(BATCH) {
+            let res: Response = self
+                .post("/graphql", &query(pairs))
+                .await?
+                .ok_or_else(|| Error::Others("GitHub GraphQL API not found".to_string()))?;
+
+            // a repository that does not exist is `null` with a NOT_FOUND error
+            let errors: Vec<&str> = res
+                .errors
+                .iter()
+                .filter(|x| x.kind.as_deref() != Some("NOT_FOUND"))
+                .map(|x| x.message.as_str())
+                .collect();
+            if !errors.is_empty() {
+                return Err(Error::Others(errors.join("; ")));
+            }
+
+            let mut repos = res.data.unwrap_or_default();
+            for (i, (owner, name)) in pairs.iter().enumerate() {
+                let k = format!("{}/{}", owner, name);
+                let details = repos
+                    .remove(&format!("r{}", i))
+                    .flatten()
+                    .map(RepoDetails::from);
+                self.cache(&self.repos, &k, details.clone());
+                found.insert(k, details);
+            }
+        }
+
+        Ok(data
+            .into_iter()
+            .map(|repository| {
+                let details = key(&repository).and_then(|k| found.get(&k).cloned().flatten());
+                EnrichedRepository {
+                    repository,
+                    details,
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,233 @@
+//! Details the trending page does not show, from the GitHub REST API.
+//! Enabled by the `enrich` feature.
+//!
+//! Requests that hit the rate limit are retried once it resets, if that is
+//! within `rate_limit_wait`, and fail with `Error::RateLimited` otherwise.
+//!
+//! ```no_run
+//! use gtrend::enrich::Client;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let data = gtrend::repos::builder().programming_language("rust").get_data().await?;
+//!
+//! let client = Client::new().token(std::env::var("GITHUB_TOKEN").unwrap());
+//! for x in client.enrich_repos(data).await? {
+//!     println!("{:?} {:?}", x.repository.full_name, x.details.map(|d| d.topics));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod developers;
+mod graphql;
+mod repos;
+
+pub use self::developers::{EnrichedDeveloper, UserDetails};
+pub use self::repos::{EnrichedRepository, RepoDetails};
+
+use crate::error::Error;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// How `enrich_repos` asks for repository details.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum Backend {
+    /// One `/repos/{author}/{name}` request per repository.
+    #[default]
+    Rest,
+    /// One aliased GraphQL query for all of them. GitHub only answers
+    /// GraphQL with a token, without one the REST API is used.
+    GraphQl,
+}
+
+/// Responses are cached per client and its clones, `/repos/tokio-rs/tokio`
+/// is only requested again once `cache_ttl` has passed.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+    concurrency: usize,
+    backend: Backend,
+    cache_ttl: Duration,
+    rate_limit_wait: Duration,
+    // when the exhausted rate limit resets
+    reset: Arc<Mutex<Option<SystemTime>>>,
+    repos: Cache<Option<RepoDetails>>,
+    users: Cache<Option<UserDetails>>,
+}
+
+#[derive(Debug)]
+struct Cached<T> {
+    value: T,
+    at: Instant,
+}
+
+type Cache<T> = Arc<Mutex<HashMap<String, Cached<T>>>>;
+
+impl Default for Client {
+    fn default() -> Self {
+        Client {
+            http: reqwest::Client::new(),
+            api_url: GITHUB_API_URL.to_string(),
+            token: None,
+            concurrency: 4,
+            backend: Backend::default(),
+            cache_ttl: Duration::from_secs(3600),
+            rate_limit_wait: Duration::ZERO,
+            reset: Arc::default(),
+            repos: Cache::default(),
+            users: Cache::default(),
+        }
+    }
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Client::default()
+    }
+
+    /// Base url of the API, e.g. of a GitHub Enterprise server or a mock.
+    pub fn api_url<T: Into<String>>(mut self, api_url: T) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Personal access token; without one GitHub allows 60 requests an hour.
+    pub fn token<T: Into<String>>(mut self, token: T) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Requests in flight at once, at least 1.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// Longest wait for an exhausted rate limit to reset, none by default.
+    pub fn rate_limit_wait(mut self, rate_limit_wait: Duration) -> Self {
+        self.rate_limit_wait = rate_limit_wait;
+        self
+    }
+
+    /// GET `path` as JSON, `None` on 404.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>, Error> {
+        self.call(Method::GET, path, None).await
+    }
+
+    /// POST `body` as JSON to `path`.
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &Value,
+    ) -> Result<Option<T>, Error> {
+        self.call(Method::POST, path, Some(body)).await
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<Option<T>, Error> {
+        loop {
+            self.wait_for_reset().await?;
+
+            let mut req = self
+                .http
+                .request(method.clone(), format!("{}{}", self.api_url, path))
+                .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+                .header(reqwest::header::USER_AGENT, "gtrend")
+                .header("X-GitHub-Api-Version", "2022-11-28");
+            if let Some(token) = self.token.as_ref() {
+                req = req.bearer_auth(token);
+            }
+            if let Some(body) = body {
+                req = req.json(body);
+            }
+
+            let res = req.send().await?;
+            let status = res.status();
+            let header = |name: &str| -> Option<u64> {
+                res.headers()
+                    .get(name)
+                    .and_then(|x| x.to_str().ok())
+                    .and_then(|x| x.trim().parse().ok())
+            };
+            let exhausted = header("x-ratelimit-remaining") == Some(0);
+            let retry_after = header("retry-after");
+            let limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || (status == reqwest::StatusCode::FORBIDDEN
+                    && (exhausted || retry_after.is_some()));
+
+            if limited || exhausted {
+                let now = SystemTime::now();
+                let reset = match (retry_after, header("x-ratelimit-reset")) {
+                    (Some(secs), _) => now + Duration::from_secs(secs),
+                    (None, Some(epoch)) => UNIX_EPOCH + Duration::from_secs(epoch),
+                    (None, None) => now + Duration::from_secs(60),
+                };
+                // never retry right away
+                *self.reset.lock().unwrap() = Some(reset.max(now + Duration::from_secs(1)));
+            }
+            if limited {
+                continue;
+            }
+
+            if status == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            let res = res.error_for_status()?;
+
+            return Ok(Some(res.json().await?));
+        }
+    }
+
+    async fn wait_for_reset(&self) -> Result<(), Error> {
+        let reset = *self.reset.lock().unwrap();
+        let wait = reset.and_then(|x| x.duration_since(SystemTime::now()).ok());
+
+        match wait {
+            Some(wait) if wait > self.rate_limit_wait => Err(Error::RateLimited(reset)),
+            Some(wait) => {
+                tokio::time::sleep(wait).await;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn cached<T: Clone>(&self, cache: &Cache<T>, key: &str) -> Option<T> {
+        let cache = cache.lock().unwrap();
+        cache
+            .get(key)
+            .filter(|x| x.at.elapsed() < self.cache_ttl)
+            .map(|x| x.value.clone())
+    }
+
+    fn cache<T>(&self, cache: &Cache<T>, key: &str, value: T) {
+        cache.lock().unwrap().insert(
+            key.to_string(),
+            Cached {
+                value,
+                at: Instant::now(),
+            },
+        );
+    }
+}
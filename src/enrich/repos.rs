@@ -0,0 +1,110 @@
+use super::{Backend, Client};
+use crate::error::Error;
+use crate::repos::Repository;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepoDetails {
+    pub topics: Vec<String>,
+    /// SPDX id such as `MIT`, or the license name when there is none.
+    pub license: Option<String>,
+    pub created_at: Option<String>,
+    /// Open issues and pull requests, like GitHub's `open_issues_count`.
+    pub open_issues: Option<u32>,
+    pub homepage: Option<String>,
+    pub default_branch: Option<String>,
+}
+
+/// `details` is `None` when the repository is gone, e.g. renamed since it
+/// was on the trending page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichedRepository {
+    #[serde(flatten)]
+    pub repository: Repository,
+    pub details: Option<RepoDetails>,
+}
+
+#[derive(Deserialize)]
+struct ApiLicense {
+    spdx_id: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiRepo {
+    #[serde(default)]
+    topics: Vec<String>,
+    license: Option<ApiLicense>,
+    created_at: Option<String>,
+    open_issues_count: Option<u32>,
+    homepage: Option<String>,
+    default_branch: Option<String>,
+}
+
+impl From<ApiRepo> for RepoDetails {
+    fn from(x: ApiRepo) -> Self {
+        let license = x.license.and_then(|l| {
+            l.spdx_id
+                .filter(|id| !id.is_empty() && id != "NOASSERTION")
+                .or(l.name)
+        });
+
+        RepoDetails {
+            topics: x.topics,
+            license,
+            created_at: x.created_at,
+            open_issues: x.open_issues_count,
+            homepage: x.homepage.filter(|x| !x.is_empty()),
+            default_branch: x.default_branch,
+        }
+    }
+}
+
+impl Client {
+    /// `/repos/{author}/{name}`, `None` when it does not exist.
+    pub async fn repo_details(
+        &self,
+        author: &str,
+        name: &str,
+    ) -> Result<Option<RepoDetails>, Error> {
+        let key = format!("{}/{}", author, name);
+        if let Some(details) = self.cached(&self.repos, &key) {
+            return Ok(details);
+        }
+
+        let details: Option<RepoDetails> = self
+            .get::<ApiRepo>(&format!("/repos/{}", key))
+            .await?
+            .map(RepoDetails::from);
+        self.cache(&self.repos, &key, details.clone());
+
+        Ok(details)
+    }
+
+    /// Look up every repository, keeping the order: `concurrency` requests
+    /// at a time, or in one query with `Backend::GraphQl`.
+    pub async fn enrich_repos(
+        &self,
+        data: Vec<Repository>,
+    ) -> Result<Vec<EnrichedRepository>, Error> {
+        if self.backend == Backend::GraphQl && self.token.is_some() {
+            return self.enrich_repos_graphql(data).await;
+        }
+
+        stream::iter(data)
+            .map(|repository| async move {
+                let details = match (repository.author.as_ref(), repository.name.as_ref()) {
+                    (Some(author), Some(name)) => self.repo_details(author, name).await?,
+                    _ => None,
+                };
+                Ok(EnrichedRepository {
+                    repository,
+                    details,
+                })
+            })
+            .buffered(self.concurrency)
+            .try_collect()
+            .await
+    }
+}
@@ -2,14 +2,51 @@ use std::fmt;
 
 #[derive(Debug)]
 pub enum Error {
+    #[cfg(feature = "fetch")]
     ReqwestError(reqwest::Error),
+    Timeout,
+    Cancelled,
+    /// The GitHub API rate limit is exhausted until the given time.
+    RateLimited(Option<std::time::SystemTime>),
+    InvalidUrl(String),
+    IoError(std::io::Error),
+    JsonError(serde_json::Error),
+    #[cfg(feature = "csv")]
+    CsvError(csv::Error),
+    #[cfg(feature = "sqlite")]
+    SqliteError(rusqlite::Error),
+    #[cfg(feature = "arrow")]
+    ArrowError(arrow::error::ArrowError),
+    #[cfg(feature = "arrow")]
+    ParquetError(parquet::errors::ParquetError),
     Others(String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
+            #[cfg(feature = "fetch")]
             Error::ReqwestError(ref x) => write!(f, "{}", x),
+            Error::Timeout => write!(f, "request timed out"),
+            Error::Cancelled => write!(f, "request cancelled"),
+            Error::RateLimited(reset) => {
+                write!(f, "GitHub API rate limit exceeded")?;
+                match reset.and_then(|x| x.duration_since(std::time::SystemTime::now()).ok()) {
+                    Some(wait) => write!(f, ", resets in {}s", wait.as_secs()),
+                    None => Ok(()),
+                }
+            }
+            Error::InvalidUrl(ref x) => write!(f, "not a github.com/trending url: {}", x),
+            Error::IoError(ref x) => write!(f, "{}", x),
+            Error::JsonError(ref x) => write!(f, "{}", x),
+            #[cfg(feature = "csv")]
+            Error::CsvError(ref x) => write!(f, "{}", x),
+            #[cfg(feature = "sqlite")]
+            Error::SqliteError(ref x) => write!(f, "{}", x),
+            #[cfg(feature = "arrow")]
+            Error::ArrowError(ref x) => write!(f, "{}", x),
+            #[cfg(feature = "arrow")]
+            Error::ParquetError(ref x) => write!(f, "{}", x),
             Error::Others(ref x) => write!(f, "{}", x),
         }
     }
@@ -27,4 +64,35 @@ macro_rules! error_wrap {
     };
 }
 
-error_wrap!(reqwest::Error, Error::ReqwestError);
+error_wrap!(std::io::Error, Error::IoError);
+error_wrap!(serde_json::Error, Error::JsonError);
+#[cfg(feature = "csv")]
+error_wrap!(csv::Error, Error::CsvError);
+#[cfg(feature = "sqlite")]
+error_wrap!(rusqlite::Error, Error::SqliteError);
+#[cfg(feature = "arrow")]
+error_wrap!(arrow::error::ArrowError, Error::ArrowError);
+#[cfg(feature = "arrow")]
+error_wrap!(parquet::errors::ParquetError, Error::ParquetError);
+
+#[cfg(feature = "fetch")]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Error {
+        if e.is_timeout() {
+            Error::Timeout
+        } else {
+            Error::ReqwestError(e)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSinceError(pub(crate) String);
+
+impl fmt::Display for ParseSinceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "expected daily, weekly or monthly, got `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseSinceError {}
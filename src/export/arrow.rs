@@ -0,0 +1,267 @@
+//! Arrow record batches and Parquet files, enabled by the `arrow` feature.
+//!
+//! Columns are named like the serialized fields. `built_by` is a list of
+//! `{username, href, avatar}` structs and a developer's `repo` a nullable
+//! `{name, description, url, label}` struct. Snapshot batches start with
+//! `taken_at` (UTC milliseconds) and the 1-based `rank`, so snapshots can be
+//! appended to one dataset.
+//!
+//! ```no_run
+//! use gtrend::export::arrow::Writer;
+//! use gtrend::momentum::Snapshot;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let snapshot = Snapshot::new(gtrend::repos::builder().get_data().await?);
+//!
+//! let file = std::fs::File::create("trending-2024-01-01.parquet")?;
+//! let mut wtr = Writer::snapshots(file)?;
+//! wtr.write_snapshot(&snapshot)?;
+//! wtr.into_inner()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::developers::Developer;
+use crate::error::Error;
+use crate::momentum::Snapshot;
+use crate::repos::Repository;
+use ::arrow::array::{
+    ArrayRef, BooleanArray, ListArray, RecordBatch, StringArray, StructArray,
+    TimestampMillisecondArray, UInt32Array,
+};
+use ::arrow::buffer::{NullBuffer, OffsetBuffer};
+use ::arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use ::parquet::arrow::ArrowWriter;
+use std::io;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+fn string(name: &str) -> Field {
+    Field::new(name, DataType::Utf8, true)
+}
+
+fn built_by_fields() -> Fields {
+    Fields::from(vec![string("username"), string("href"), string("avatar")])
+}
+
+fn repo_fields() -> Fields {
+    Fields::from(vec![
+        string("name"),
+        string("description"),
+        string("url"),
+        string("label"),
+    ])
+}
+
+fn built_by_item() -> Arc<Field> {
+    Arc::new(Field::new(
+        "item",
+        DataType::Struct(built_by_fields()),
+        false,
+    ))
+}
+
+fn repos_fields() -> Vec<Field> {
+    vec![
+        string("author"),
+        string("name"),
+        string("full_name"),
+        string("url"),
+        string("avatar"),
+        string("description"),
+        string("programming_language"),
+        string("lang_color"),
+        Field::new("stars", DataType::UInt32, true),
+        Field::new("forks", DataType::UInt32, true),
+        Field::new("current_star", DataType::UInt32, true),
+        string("since"),
+        Field::new("sponsorable", DataType::Boolean, false),
+        Field::new("built_by", DataType::List(built_by_item()), false),
+    ]
+}
+
+pub fn repos_schema() -> SchemaRef {
+    Arc::new(Schema::new(repos_fields()))
+}
+
+pub fn developers_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        string("name"),
+        string("username"),
+        string("url"),
+        string("avatar"),
+        string("organization"),
+        Field::new("sponsorable", DataType::Boolean, false),
+        string("sponsor_url"),
+        Field::new("repo", DataType::Struct(repo_fields()), true),
+    ]))
+}
+
+/// `taken_at` and `rank` followed by the columns of `repos_schema`.
+pub fn snapshots_schema() -> SchemaRef {
+    let mut fields = vec![
+        Field::new(
+            "taken_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("rank", DataType::UInt32, false),
+    ];
+    fields.extend(repos_fields());
+    Arc::new(Schema::new(fields))
+}
+
+fn strings<'a, T: 'a>(
+    data: impl IntoIterator<Item = &'a T>,
+    f: impl Fn(&'a T) -> Option<&'a str>,
+) -> ArrayRef {
+    Arc::new(data.into_iter().map(f).collect::<StringArray>())
+}
+
+fn repos_columns(data: &[Repository]) -> Vec<ArrayRef> {
+    let built_by: Vec<_> = data.iter().flat_map(|x| x.built_by.iter()).collect();
+    let built_by = StructArray::new(
+        built_by_fields(),
+        vec![
+            strings(built_by.iter().copied(), |b| b.username.as_deref()),
+            strings(built_by.iter().copied(), |b| b.href.as_deref()),
+            strings(built_by.iter().copied(), |b| b.avatar.as_deref()),
+        ],
+        None,
+    );
+    let offsets = OffsetBuffer::from_lengths(data.iter().map(|x| x.built_by.len()));
+
+    vec![
+        strings(data, |x| x.author.as_deref()),
+        strings(data, |x| x.name.as_deref()),
+        strings(data, |x| x.full_name.as_deref()),
+        strings(data, |x| x.url.as_deref()),
+        strings(data, |x| x.avatar.as_deref()),
+        strings(data, |x| x.description.as_deref()),
+        strings(data, |x| x.programming_language.as_deref()),
+        strings(data, |x| x.lang_color.as_deref()),
+        Arc::new(data.iter().map(|x| x.stars).collect::<UInt32Array>()),
+        Arc::new(data.iter().map(|x| x.forks).collect::<UInt32Array>()),
+        Arc::new(data.iter().map(|x| x.current_star).collect::<UInt32Array>()),
+        strings(data, |x| x.since.as_deref()),
+        Arc::new(BooleanArray::from(
+            data.iter().map(|x| x.sponsorable).collect::<Vec<_>>(),
+        )),
+        Arc::new(ListArray::new(
+            built_by_item(),
+            offsets,
+            Arc::new(built_by),
+            None,
+        )),
+    ]
+}
+
+pub fn repos_to_batch(data: &[Repository]) -> Result<RecordBatch, Error> {
+    Ok(RecordBatch::try_new(repos_schema(), repos_columns(data))?)
+}
+
+pub fn developers_to_batch(data: &[Developer]) -> Result<RecordBatch, Error> {
+    let repos: Vec<_> = data.iter().map(|x| x.repo.as_ref()).collect();
+    let repo = StructArray::new(
+        repo_fields(),
+        vec![
+            strings(&repos, |r| r.and_then(|r| r.name.as_deref())),
+            strings(&repos, |r| r.and_then(|r| r.description.as_deref())),
+            strings(&repos, |r| r.and_then(|r| r.url.as_deref())),
+            strings(&repos, |r| r.and_then(|r| r.label.as_deref())),
+        ],
+        Some(NullBuffer::from(
+            repos.iter().map(|r| r.is_some()).collect::<Vec<_>>(),
+        )),
+    );
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(data, |x| x.name.as_deref()),
+        strings(data, |x| x.username.as_deref()),
+        strings(data, |x| x.url.as_deref()),
+        strings(data, |x| x.avatar.as_deref()),
+        strings(data, |x| x.organization.as_deref()),
+        Arc::new(BooleanArray::from(
+            data.iter().map(|x| x.sponsorable).collect::<Vec<_>>(),
+        )),
+        strings(data, |x| x.sponsor_url.as_deref()),
+        Arc::new(repo),
+    ];
+    Ok(RecordBatch::try_new(developers_schema(), columns)?)
+}
+
+pub fn snapshot_to_batch(snapshot: &Snapshot) -> Result<RecordBatch, Error> {
+    let taken_at = snapshot
+        .taken_at
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_millis() as i64)
+        .unwrap_or_default();
+    let len = snapshot.data.len();
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(TimestampMillisecondArray::from(vec![taken_at; len]).with_timezone("UTC")),
+        Arc::new(UInt32Array::from_iter_values(1..=len as u32)),
+    ];
+    columns.extend(repos_columns(&snapshot.data));
+    Ok(RecordBatch::try_new(snapshots_schema(), columns)?)
+}
+
+/// Parquet file of one schema; the footer is written by `into_inner`.
+pub struct Writer<W: io::Write + Send> {
+    inner: ArrowWriter<W>,
+}
+
+impl<W: io::Write + Send> Writer<W> {
+    pub fn new(writer: W, schema: SchemaRef) -> Result<Self, Error> {
+        Ok(Writer {
+            inner: ArrowWriter::try_new(writer, schema, None)?,
+        })
+    }
+
+    pub fn repos(writer: W) -> Result<Self, Error> {
+        Writer::new(writer, repos_schema())
+    }
+
+    pub fn developers(writer: W) -> Result<Self, Error> {
+        Writer::new(writer, developers_schema())
+    }
+
+    pub fn snapshots(writer: W) -> Result<Self, Error> {
+        Writer::new(writer, snapshots_schema())
+    }
+
+    /// Fails when `batch` does not have the schema of the writer.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        Ok(self.inner.write(batch)?)
+    }
+
+    pub fn write_repos(&mut self, data: &[Repository]) -> Result<(), Error> {
+        self.write(&repos_to_batch(data)?)
+    }
+
+    pub fn write_developers(&mut self, data: &[Developer]) -> Result<(), Error> {
+        self.write(&developers_to_batch(data)?)
+    }
+
+    pub fn write_snapshot(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        self.write(&snapshot_to_batch(snapshot)?)
+    }
+
+    /// Write the buffered rows and the footer.
+    pub fn into_inner(self) -> Result<W, Error> {
+        Ok(self.inner.into_inner()?)
+    }
+}
+
+pub fn write_repos<W: io::Write + Send>(writer: W, data: &[Repository]) -> Result<(), Error> {
+    let mut wtr = Writer::repos(writer)?;
+    wtr.write_repos(data)?;
+    wtr.into_inner()?;
+    Ok(())
+}
+
+pub fn write_developers<W: io::Write + Send>(writer: W, data: &[Developer]) -> Result<(), Error> {
+    let mut wtr = Writer::developers(writer)?;
+    wtr.write_developers(data)?;
+    wtr.into_inner()?;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use crate::developers::Developer;
+use crate::error::Error;
+use crate::repos::Repository;
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub(crate) trait Column<T> {
+    #[cfg(feature = "csv")]
+    fn header(&self) -> &'static str;
+    fn value(&self, data: &T) -> String;
+}
+
+fn opt<T: Display>(val: &Option<T>) -> String {
+    val.as_ref().map(|x| x.to_string()).unwrap_or_default()
+}
+
+macro_rules! columns {
+    ($name:ident, $data:ty, { $($variant:ident => $header:literal => $value:expr,)* }) => {
+        #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            pub fn all() -> &'static [$name] {
+                &[$($name::$variant,)*]
+            }
+        }
+
+        impl Column<$data> for $name {
+            #[cfg(feature = "csv")]
+            fn header(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $header,)*
+                }
+            }
+
+            fn value(&self, data: &$data) -> String {
+                let f: fn(&$data) -> String = match self {
+                    $($name::$variant => $value,)*
+                };
+                f(data)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.trim() {
+                    $($header => Ok($name::$variant),)*
+                    _ => Err(Error::Others(format!("unknown column `{}`", s))),
+                }
+            }
+        }
+    };
+}
+
+columns!(RepositoryColumn, Repository, {
+    Author => "author" => |x| opt(&x.author),
+    Name => "name" => |x| opt(&x.name),
+    FullName => "full_name" => |x| opt(&x.full_name),
+    Url => "url" => |x| opt(&x.url),
+    Avatar => "avatar" => |x| opt(&x.avatar),
+    Description => "description" => |x| opt(&x.description),
+    ProgrammingLanguage => "programming_language" => |x| opt(&x.programming_language),
+    LangColor => "lang_color" => |x| opt(&x.lang_color),
+    Stars => "stars" => |x| opt(&x.stars),
+    Forks => "forks" => |x| opt(&x.forks),
+    CurrentStar => "current_star" => |x| opt(&x.current_star),
+    Since => "since" => |x| opt(&x.since),
+    Sponsorable => "sponsorable" => |x| x.sponsorable.to_string(),
+    BuiltBy => "built_by" => |x| {
+        x.built_by
+            .iter()
+            .filter_map(|b| b.username.as_deref())
+            .collect::<Vec<_>>()
+            .join(";")
+    },
+});
+
+columns!(DeveloperColumn, Developer, {
+    Name => "name" => |x| opt(&x.name),
+    Username => "username" => |x| opt(&x.username),
+    Url => "url" => |x| opt(&x.url),
+    Avatar => "avatar" => |x| opt(&x.avatar),
+    Organization => "organization" => |x| opt(&x.organization),
+    Sponsorable => "sponsorable" => |x| x.sponsorable.to_string(),
+    SponsorUrl => "sponsor_url" => |x| opt(&x.sponsor_url),
+    RepoName => "repo_name" => |x| x.repo.as_ref().map(|r| opt(&r.name)).unwrap_or_default(),
+    RepoDescription => "repo_description" => |x| {
+        x.repo.as_ref().map(|r| opt(&r.description)).unwrap_or_default()
+    },
+    RepoUrl => "repo_url" => |x| x.repo.as_ref().map(|r| opt(&r.url)).unwrap_or_default(),
+    RepoLabel => "repo_label" => |x| x.repo.as_ref().map(|r| opt(&r.label)).unwrap_or_default(),
+});
@@ -0,0 +1,80 @@
+//! CSV output with one row per repository or developer. Nested values are
+//! flattened: `built_by` becomes the `;` joined usernames and a developer's
+//! repo is split into `repo_name`, `repo_description`, ...
+//!
+//! ```no_run
+//! use gtrend::export::csv::{self, RepositoryColumn};
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let data = gtrend::repos::builder().get_data().await?;
+//!
+//! let columns = [RepositoryColumn::FullName, RepositoryColumn::CurrentStar];
+//! csv::write_repos(std::io::stdout(), &data, &columns)?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub use super::{DeveloperColumn, RepositoryColumn};
+
+use super::Column;
+use crate::developers::Developer;
+use crate::error::Error;
+use crate::repos::Repository;
+use std::io;
+
+fn write<W, T, C>(writer: W, data: &[T], columns: &[C]) -> Result<(), Error>
+where
+    W: io::Write,
+    C: Column<T>,
+{
+    let mut wtr = ::csv::Writer::from_writer(writer);
+    wtr.write_record(columns.iter().map(|c| c.header()))?;
+    for x in data {
+        wtr.write_record(columns.iter().map(|c| c.value(x)))?;
+    }
+    wtr.flush()?;
+
+    Ok(())
+}
+
+/// Write `data` with a header row; an empty `columns` means all of them.
+pub fn write_repos<W: io::Write>(
+    writer: W,
+    data: &[Repository],
+    columns: &[RepositoryColumn],
+) -> Result<(), Error> {
+    let columns = if columns.is_empty() {
+        RepositoryColumn::all()
+    } else {
+        columns
+    };
+    write(writer, data, columns)
+}
+
+/// Write `data` with a header row; an empty `columns` means all of them.
+pub fn write_developers<W: io::Write>(
+    writer: W,
+    data: &[Developer],
+    columns: &[DeveloperColumn],
+) -> Result<(), Error> {
+    let columns = if columns.is_empty() {
+        DeveloperColumn::all()
+    } else {
+        columns
+    };
+    write(writer, data, columns)
+}
+
+pub fn repos_to_string(data: &[Repository], columns: &[RepositoryColumn]) -> Result<String, Error> {
+    let mut buf = Vec::new();
+    write_repos(&mut buf, data, columns)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+pub fn developers_to_string(
+    data: &[Developer],
+    columns: &[DeveloperColumn],
+) -> Result<String, Error> {
+    let mut buf = Vec::new();
+    write_developers(&mut buf, data, columns)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
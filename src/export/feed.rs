@@ -0,0 +1,232 @@
+//! RSS 2.0 and Atom feeds of a trending query.
+//!
+//! Items are identified by the repository or developer url, so a feed reader
+//! only shows entries that were not on the page the last time it polled.
+//!
+//! ```no_run
+//! use gtrend::export::feed::Feed;
+//! use gtrend::Since;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let builder = gtrend::repos::builder().programming_language("rust").since(Since::Weekly);
+//! let data = builder.get_data().await?;
+//!
+//! let rss = Feed::new(builder.query()).repos_rss(&data);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::html::escape;
+use crate::developers::Developer;
+use crate::query::{Kind, TrendingQuery};
+use crate::repos::Repository;
+use crate::Since;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub title: String,
+    pub link: String,
+    pub updated: SystemTime,
+}
+
+struct Item<'a> {
+    title: String,
+    link: &'a str,
+    description: String,
+    category: Option<&'a str>,
+}
+
+impl Feed {
+    /// Feed titled after `query`, e.g. "Trending rust repositories this week",
+    /// linking to its github.com/trending page and updated now.
+    pub fn new(query: &TrendingQuery) -> Self {
+        let kind = match query.kind {
+            Kind::Repositories => "repositories",
+            Kind::Developers => "developers",
+        };
+        let period = match query.since.unwrap_or_default() {
+            Since::Daily => "today",
+            Since::Weekly => "this week",
+            Since::Monthly => "this month",
+        };
+        let title = match query.language.as_ref() {
+            Some(lang) => format!("Trending {} {} {}", lang, kind, period),
+            None => format!("Trending {} {}", kind, period),
+        };
+
+        Feed {
+            title,
+            link: query.to_url(),
+            updated: SystemTime::now(),
+        }
+    }
+
+    pub fn repos_rss(&self, data: &[Repository]) -> String {
+        self.rss(data.iter().filter_map(repo_item))
+    }
+
+    pub fn repos_atom(&self, data: &[Repository]) -> String {
+        self.atom(data.iter().filter_map(repo_item))
+    }
+
+    pub fn developers_rss(&self, data: &[Developer]) -> String {
+        self.rss(data.iter().filter_map(developer_item))
+    }
+
+    pub fn developers_atom(&self, data: &[Developer]) -> String {
+        self.atom(data.iter().filter_map(developer_item))
+    }
+
+    fn rss<'a>(&self, items: impl Iterator<Item = Item<'a>>) -> String {
+        let mut out = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rss version=\"2.0\">\n<channel>\n\
+             <title>{title}</title>\n<link>{link}</link>\n<description>{title}</description>\n\
+             <lastBuildDate>{updated}</lastBuildDate>\n",
+            title = escape(&self.title),
+            link = escape(&self.link),
+            updated = rfc2822(self.updated)
+        );
+        for item in items {
+            out.push_str(&format!(
+                "<item>\n<title>{}</title>\n<link>{link}</link>\n<guid isPermaLink=\"true\">{link}</guid>\n<description>{}</description>\n",
+                escape(&item.title),
+                escape(&item.description),
+                link = escape(item.link)
+            ));
+            if let Some(category) = item.category {
+                out.push_str(&format!("<category>{}</category>\n", escape(category)));
+            }
+            out.push_str("</item>\n");
+        }
+        out.push_str("</channel>\n</rss>\n");
+
+        out
+    }
+
+    fn atom<'a>(&self, items: impl Iterator<Item = Item<'a>>) -> String {
+        let mut out = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+             <title>{title}</title>\n<id>{link}</id>\n<link href=\"{link}\"/>\n<updated>{updated}</updated>\n\
+             <author><name>GitHub Trending</name></author>\n",
+            title = escape(&self.title),
+            link = escape(&self.link),
+            updated = rfc3339(self.updated)
+        );
+        for item in items {
+            out.push_str(&format!(
+                "<entry>\n<title>{}</title>\n<id>{link}</id>\n<link href=\"{link}\"/>\n<updated>{updated}</updated>\n<summary>{}</summary>\n",
+                escape(&item.title),
+                escape(&item.description),
+                link = escape(item.link),
+                updated = rfc3339(self.updated)
+            ));
+            if let Some(category) = item.category {
+                out.push_str(&format!("<category term=\"{}\"/>\n", escape(category)));
+            }
+            out.push_str("</entry>\n");
+        }
+        out.push_str("</feed>\n");
+
+        out
+    }
+}
+
+// rows without a url have nothing stable to be identified by
+fn repo_item(x: &Repository) -> Option<Item<'_>> {
+    Some(Item {
+        title: x.full_name.clone().or_else(|| x.name.clone())?,
+        link: x.url.as_deref()?,
+        description: x.description.clone().unwrap_or_default(),
+        category: x.programming_language.as_deref(),
+    })
+}
+
+fn developer_item(x: &Developer) -> Option<Item<'_>> {
+    let username = x.username.as_deref()?;
+    let title = match x.name.as_deref() {
+        Some(name) if name != username => format!("{} ({})", name, username),
+        _ => username.to_string(),
+    };
+    let description = x
+        .repo
+        .as_ref()
+        .map(|r| match (r.name.as_deref(), r.description.as_deref()) {
+            (Some(name), Some(desc)) => format!("{}: {}", name, desc),
+            (name, desc) => name.or(desc).unwrap_or_default().to_string(),
+        })
+        .unwrap_or_default();
+
+    Some(Item {
+        title,
+        link: x.url.as_deref()?,
+        description,
+        category: None,
+    })
+}
+
+const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+struct DateTime {
+    days: u64,
+    year: u64,
+    month: u64,
+    day: u64,
+    secs: u64,
+}
+
+fn date_time(time: SystemTime) -> DateTime {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default();
+    let days = secs / 86_400;
+
+    // civil_from_days, http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    DateTime {
+        days,
+        year,
+        month,
+        day,
+        secs: secs % 86_400,
+    }
+}
+
+fn rfc2822(time: SystemTime) -> String {
+    let t = date_time(time);
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} +0000",
+        DAYS[((t.days + 4) % 7) as usize],
+        t.day,
+        MONTHS[(t.month - 1) as usize],
+        t.year,
+        t.secs / 3_600,
+        t.secs % 3_600 / 60,
+        t.secs % 60
+    )
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    let t = date_time(time);
+    format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        t.year,
+        t.month,
+        t.day,
+        t.secs / 3_600,
+        t.secs % 3_600 / 60,
+        t.secs % 60
+    )
+}
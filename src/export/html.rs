@@ -0,0 +1,83 @@
+//! Standalone HTML pages with avatars, language color swatches and links.
+//!
+//! ```no_run
+//! use gtrend::export::html;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let data = gtrend::repos::builder().programming_language("rust").get_data().await?;
+//!
+//! std::fs::write("trending.html", html::render_repos(&data, &html::repos_page()))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{DeveloperColumn, RepositoryColumn, Template};
+use crate::developers::Developer;
+use crate::repos::Repository;
+
+const STYLE: &str = "<style>
+body { font-family: -apple-system, BlinkMacSystemFont, sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+td, th { padding: 0.4em 0.8em; border-bottom: 1px solid #d0d7de; text-align: left; }
+img.avatar { border-radius: 50%; vertical-align: middle; }
+.swatch { display: inline-block; width: 0.8em; height: 0.8em; border-radius: 50%; }
+</style>";
+
+fn page(title: &str, columns: &str, row: &str) -> Template {
+    Template::new(
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n{style}\n</head>\n<body>\n<h1>{title}</h1>\n<table>\n<tr>{columns}</tr>\n",
+            title = title,
+            style = STYLE,
+            columns = columns
+        ),
+        row,
+        "</table>\n</body>\n</html>\n",
+    )
+}
+
+pub fn repos_page() -> Template {
+    page(
+        "Trending repositories",
+        "<th>#</th><th></th><th>Repository</th><th>Language</th><th>Stars</th><th>Forks</th><th>Stars since</th>",
+        "<tr><td>{rank}</td>\
+         <td><img class=\"avatar\" src=\"{avatar}\" width=\"32\" height=\"32\" alt=\"{author}\"></td>\
+         <td><a href=\"{url}\">{full_name}</a><br><small>{description}</small></td>\
+         <td><span class=\"swatch\" style=\"background-color: {lang_color}\"></span> {programming_language}</td>\
+         <td>{stars}</td><td>{forks}</td><td>{current_star}</td></tr>\n",
+    )
+}
+
+pub fn developers_page() -> Template {
+    page(
+        "Trending developers",
+        "<th>#</th><th></th><th>Developer</th><th>Popular repo</th>",
+        "<tr><td>{rank}</td>\
+         <td><img class=\"avatar\" src=\"{avatar}\" width=\"32\" height=\"32\" alt=\"{username}\"></td>\
+         <td><a href=\"{url}\">{name}</a><br><small>{username}</small></td>\
+         <td><a href=\"{repo_url}\">{repo_name}</a><br><small>{repo_description}</small></td></tr>\n",
+    )
+}
+
+pub fn render_repos(data: &[Repository], template: &Template) -> String {
+    template.render::<_, RepositoryColumn>(data, escape)
+}
+
+pub fn render_developers(data: &[Developer], template: &Template) -> String {
+    template.render::<_, DeveloperColumn>(data, escape)
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
@@ -0,0 +1,72 @@
+//! Markdown tables and lists, e.g. for a newsletter.
+//!
+//! ```no_run
+//! use gtrend::export::{markdown, Template};
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let data = gtrend::repos::builder().programming_language("rust").get_data().await?;
+//!
+//! let table: String = markdown::render_repos(&data, &markdown::repos_table());
+//! let custom = Template::new("", "- **{full_name}** {current_star} stars {since}\n", "");
+//! let list: String = markdown::render_repos(&data, &custom);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{DeveloperColumn, RepositoryColumn, Template};
+use crate::developers::Developer;
+use crate::repos::Repository;
+
+pub fn repos_table() -> Template {
+    Template::new(
+        "| # | Repository | Description | Language | Stars | Forks | Stars since |\n\
+         | --- | --- | --- | --- | --- | --- | --- |\n",
+        "| {rank} | [{full_name}]({url}) | {description} | {programming_language} \
+         | {stars} | {forks} | {current_star} |\n",
+        "",
+    )
+}
+
+pub fn repos_list() -> Template {
+    Template::new(
+        "",
+        "{rank}. [{full_name}]({url}): {description} ({current_star} stars {since})\n",
+        "",
+    )
+}
+
+pub fn developers_table() -> Template {
+    Template::new(
+        "| # | Developer | Username | Popular repo | Description |\n\
+         | --- | --- | --- | --- | --- |\n",
+        "| {rank} | [{name}]({url}) | {username} | {repo_name} | {repo_description} |\n",
+        "",
+    )
+}
+
+pub fn developers_list() -> Template {
+    Template::new("", "{rank}. [{name}]({url}) ({username}) {repo_name}\n", "")
+}
+
+pub fn render_repos(data: &[Repository], template: &Template) -> String {
+    template.render::<_, RepositoryColumn>(data, escape)
+}
+
+pub fn render_developers(data: &[Developer], template: &Template) -> String {
+    template.render::<_, DeveloperColumn>(data, escape)
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '|' | '[' | ']' | '<' | '>' | '*' | '`' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' | '\r' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
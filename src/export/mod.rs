@@ -0,0 +1,21 @@
+//! Writers for other output formats than `get_data_json`.
+//!
+//! The flattened fields of `Repository` and `Developer` are shared by all
+//! writers as `RepositoryColumn` / `DeveloperColumn`, named like the
+//! serialized fields (`full_name`, `current_star`, `repo_name`, ...).
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+mod columns;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod feed;
+pub mod html;
+pub mod markdown;
+pub mod ndjson;
+mod template;
+
+pub use columns::{DeveloperColumn, RepositoryColumn};
+pub use template::Template;
+
+pub(crate) use columns::Column;
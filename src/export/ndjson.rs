@@ -0,0 +1,103 @@
+//! Newline delimited JSON, one compact record per line, for `jq`, log
+//! shippers and the like.
+//!
+//! `Writer` keeps the underlying writer open across several queries, so the
+//! results of a fan-out can be written as each of them comes in:
+//!
+//! ```no_run
+//! use gtrend::export::ndjson::Writer;
+//! use gtrend::Since;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let mut out = Writer::new(std::io::stdout().lock());
+//! for lang in &["rust", "go", "zig"] {
+//!     let builder = gtrend::repos::builder().programming_language(*lang).since(Since::Weekly);
+//!     let data = builder.get_data().await?;
+//!     out.write_query(builder.query(), &data)?;
+//! }
+//! out.flush()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::developers::Developer;
+use crate::error::Error;
+use crate::query::TrendingQuery;
+use crate::repos::Repository;
+use serde::Serialize;
+use std::io::{self, Write};
+
+pub struct Writer<W: io::Write> {
+    inner: io::BufWriter<W>,
+}
+
+// a record tagged with the query it came from
+#[derive(Serialize)]
+struct Tagged<'a, T> {
+    query: &'a TrendingQuery,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+impl<W: io::Write> Writer<W> {
+    pub fn new(writer: W) -> Self {
+        Writer {
+            inner: io::BufWriter::new(writer),
+        }
+    }
+
+    pub fn write<T: Serialize>(&mut self, record: &T) -> Result<(), Error> {
+        serde_json::to_writer(&mut self.inner, record)?;
+        self.inner.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn write_all<T: Serialize>(&mut self, records: &[T]) -> Result<(), Error> {
+        records.iter().try_for_each(|x| self.write(x))
+    }
+
+    /// Like `write_all`, with each record carrying a `query` field.
+    pub fn write_query<T: Serialize>(
+        &mut self,
+        query: &TrendingQuery,
+        records: &[T],
+    ) -> Result<(), Error> {
+        records
+            .iter()
+            .try_for_each(|data| self.write(&Tagged { query, data }))
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> Result<W, Error> {
+        self.inner
+            .into_inner()
+            .map_err(|e| Error::IoError(e.into_error()))
+    }
+}
+
+pub fn write_repos<W: io::Write>(writer: W, data: &[Repository]) -> Result<(), Error> {
+    let mut wtr = Writer::new(writer);
+    wtr.write_all(data)?;
+    wtr.flush()
+}
+
+pub fn write_developers<W: io::Write>(writer: W, data: &[Developer]) -> Result<(), Error> {
+    let mut wtr = Writer::new(writer);
+    wtr.write_all(data)?;
+    wtr.flush()
+}
+
+pub fn repos_to_string(data: &[Repository]) -> Result<String, Error> {
+    let mut buf = Vec::new();
+    write_repos(&mut buf, data)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+pub fn developers_to_string(data: &[Developer]) -> Result<String, Error> {
+    let mut buf = Vec::new();
+    write_developers(&mut buf, data)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
@@ -0,0 +1,90 @@
+use super::Column;
+use std::str::FromStr;
+
+/// Text around and for every item of a report. `row` is filled in per item:
+/// `{rank}` and every column name in braces (`{full_name}`, `{lang_color}`,
+/// `{repo_name}`, ...) are replaced by the escaped value, anything else in
+/// braces, such as CSS, is kept as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub header: String,
+    pub row: String,
+    pub footer: String,
+}
+
+impl Template {
+    pub fn new<H, R, F>(header: H, row: R, footer: F) -> Self
+    where
+        H: Into<String>,
+        R: Into<String>,
+        F: Into<String>,
+    {
+        Template {
+            header: header.into(),
+            row: row.into(),
+            footer: footer.into(),
+        }
+    }
+
+    pub(crate) fn render<T, C>(&self, data: &[T], escape: fn(&str) -> String) -> String
+    where
+        C: Column<T> + FromStr,
+    {
+        let ranked: Vec<(usize, &T)> = data.iter().enumerate().map(|(i, x)| (i + 1, x)).collect();
+        self.render_ranked::<T, C>(&ranked, escape)
+    }
+
+    /// Like `render`, for rows that keep their own rank, e.g. new entries.
+    pub(crate) fn render_ranked<T, C>(
+        &self,
+        data: &[(usize, &T)],
+        escape: fn(&str) -> String,
+    ) -> String
+    where
+        C: Column<T> + FromStr,
+    {
+        let mut out = self.header.clone();
+        for (rank, x) in data {
+            out.push_str(&fill::<T, C>(&self.row, *rank, x, escape));
+        }
+        out.push_str(&self.footer);
+
+        out
+    }
+}
+
+fn fill<T, C>(row: &str, rank: usize, data: &T, escape: fn(&str) -> String) -> String
+where
+    C: Column<T> + FromStr,
+{
+    let mut out = String::new();
+    let mut rest = row;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let key: Option<&str> = after
+            .find('}')
+            .map(|end| &after[..end])
+            .filter(|x| !x.is_empty() && x.bytes().all(|b| b.is_ascii_lowercase() || b == b'_'));
+        let value: Option<String> = key.and_then(|x| match x {
+            "rank" => Some(rank.to_string()),
+            _ => x.parse::<C>().ok().map(|col| escape(&col.value(data))),
+        });
+
+        match (key, value) {
+            (Some(key), Some(value)) => {
+                out.push_str(&value);
+                rest = &after[key.len() + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+
+    out
+}
@@ -1,6 +1,7 @@
 use crate::{By, Language};
 use serde_json::Value;
 
+#[allow(clippy::let_and_return)]
 pub fn get_data() -> Vec<Language> {
     let bytes = include_bytes!("../languages.json");
     let data = Language::get_data(bytes);
@@ -1,15 +1,45 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod developers;
+#[cfg(feature = "enrich")]
+pub mod enrich;
 pub mod error;
+pub mod export;
+#[cfg(feature = "catalog")]
 pub mod languages;
+pub mod momentum;
+#[cfg(feature = "notify")]
+pub mod notify;
+pub mod periods;
+pub mod query;
 pub mod repos;
+pub mod schema;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "catalog")]
 pub mod spoken_languages;
-
-pub use error::Error;
+pub mod stats;
+#[cfg(feature = "sqlite")]
+pub mod storage;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "async")]
+pub mod watch;
+
+pub use error::{Error, ParseSinceError};
+pub use query::TrendingQuery;
+pub use schema::Schema;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::borrow::Borrow;
-
+use std::fmt;
+use std::str::FromStr;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use tokio_util::sync::CancellationToken;
+
+#[cfg(feature = "parse")]
 const GITHUB_BASE_URL: &str = "https://github.com";
 const GITHUB_TRENDING_URL: &str = "https://github.com/trending";
 
@@ -21,6 +51,7 @@ pub struct Language {
     pub name: String,
 }
 
+#[cfg(feature = "catalog")]
 pub enum By<'a> {
     Name(&'a str),
     UrlParam(&'a str),
@@ -43,15 +74,17 @@ impl Language {
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Since {
+    #[default]
     Daily,
     Weekly,
     Monthly,
 }
 
 impl Since {
-    pub fn to_str(&self) -> &str {
+    pub fn to_str(&self) -> &'static str {
         match self {
             Self::Daily => "daily",
             Self::Weekly => "weekly",
@@ -59,35 +92,92 @@ impl Since {
         }
     }
 
-    pub fn to_string(&self) -> String {
-        let daily = String::from("daily");
-        let weekly = String::from("weekly");
-        let monthly = String::from("monthly");
+    pub fn all() -> impl Iterator<Item = Since> {
+        [Self::Daily, Self::Weekly, Self::Monthly].iter().copied()
+    }
+}
 
-        match self {
-            Self::Daily => daily,
-            Self::Weekly => weekly,
-            Self::Monthly => monthly,
-        }
+impl fmt::Display for Since {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.to_str())
     }
+}
+
+impl FromStr for Since {
+    type Err = ParseSinceError;
 
-    pub fn from_str<T: Borrow<str>>(s: T) -> Option<Self> {
-        let s = s.borrow();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_ref() {
-            "daily" => Some(Self::Daily),
-            "weekly" => Some(Self::Weekly),
-            "monthly" => Some(Self::Monthly),
-            _ => None,
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            _ => Err(ParseSinceError(s.to_string())),
         }
     }
 }
 
-async fn fetch_html(url: &str) -> Result<String, error::Error> {
-    let resp = reqwest::get(url).await?.text().await?;
-    Ok(resp)
+/// Any case, like `FromStr`.
+impl<'de> Deserialize<'de> for Since {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FetchOptions {
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    #[cfg(feature = "async")]
+    cancel: Option<CancellationToken>,
 }
 
-#[cfg(test)]
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl FetchOptions {
+    pub(crate) fn timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    pub(crate) fn deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn cancel_token(&mut self, token: CancellationToken) {
+        self.cancel = Some(token);
+    }
+}
+
+#[cfg(feature = "async")]
+async fn fetch_html(url: &str, opts: &FetchOptions) -> Result<String, error::Error> {
+    let mut req = reqwest::Client::new().get(url);
+    if let Some(timeout) = opts.timeout {
+        req = req.timeout(timeout);
+    }
+
+    let fetch = async { Ok::<_, error::Error>(req.send().await?.text().await?) };
+    let fetch = async {
+        match opts.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), fetch)
+                .await
+                .map_err(|_| error::Error::Timeout)?,
+            None => fetch.await,
+        }
+    };
+
+    match opts.cancel.as_ref() {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(error::Error::Cancelled),
+            resp = fetch => resp,
+        },
+        None => fetch.await,
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+#[allow(clippy::len_zero)]
 mod tests {
     use super::*;
     use crate::developers;
@@ -96,20 +186,188 @@ mod tests {
 
     // const SINCE: Since = Since::Daily;
 
+    // accepts connections but never answers
+    fn silent_server() -> (std::net::TcpListener, String) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    // answers every request with the first unused response for its path,
+    // `*` matches any path, and records the raw requests, headers and body;
+    // a response is (path, status, extra header lines, body)
+    #[cfg(any(feature = "notify", feature = "enrich"))]
+    async fn mock_server(
+        responses: Vec<(&'static str, u16, &'static str, &'static str)>,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use std::sync::{Arc, Mutex};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let responses = Arc::new(Mutex::new(responses));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let (recorded, responses) = (recorded.clone(), responses.clone());
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    let req = loop {
+                        let n = socket.read(&mut chunk).await.unwrap();
+                        buf.extend_from_slice(&chunk[..n]);
+                        let req = String::from_utf8_lossy(&buf).to_string();
+                        let end = match req.find("\r\n\r\n") {
+                            Some(end) => end,
+                            None if n == 0 => return,
+                            None => continue,
+                        };
+                        let len = req[..end]
+                            .lines()
+                            .find_map(|x| {
+                                let (k, v) = x.split_once(':')?;
+                                k.eq_ignore_ascii_case("content-length")
+                                    .then(|| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if n == 0 || buf.len() >= end + 4 + len {
+                            break req;
+                        }
+                    };
+
+                    let path = req.split(' ').nth(1).unwrap_or_default().to_string();
+                    let (status, headers, body) = {
+                        let mut responses = responses.lock().unwrap();
+                        match responses.iter().position(|x| x.0 == "*" || x.0 == path) {
+                            Some(i) => {
+                                let (_, status, headers, body) = responses.remove(i);
+                                (status, headers, body)
+                            }
+                            None => (404, "", ""),
+                        }
+                    };
+                    recorded.lock().unwrap().push(req);
+
+                    let res = format!(
+                        "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+                        status,
+                        body.len(),
+                        headers,
+                        body
+                    );
+                    socket.write_all(res.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        (url, requests)
+    }
+
     #[tokio::test]
     async fn fetch_html_github_repo() {
         let github_url: &str = "https://github.com/trending";
-        let html = fetch_html(github_url).await;
+        let html = fetch_html(github_url, &FetchOptions::default()).await;
         assert!(html.is_ok());
     }
 
     #[tokio::test]
     async fn fetch_html_github_developers() {
         let github_url: &str = "https://github.com/trending/developers";
-        let html = fetch_html(github_url).await;
+        let html = fetch_html(github_url, &FetchOptions::default()).await;
         assert!(html.is_ok());
     }
 
+    #[tokio::test]
+    async fn fetch_html_timeout() {
+        let (_listener, url) = silent_server();
+        let mut opts = FetchOptions::default();
+        opts.timeout(Duration::from_millis(100));
+
+        let html = fetch_html(&url, &opts).await;
+        assert!(matches!(html, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn fetch_html_deadline() {
+        let (_listener, url) = silent_server();
+        let mut opts = FetchOptions::default();
+        opts.deadline(Instant::now() + Duration::from_millis(100));
+
+        let html = fetch_html(&url, &opts).await;
+        assert!(matches!(html, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn fetch_html_cancelled() {
+        let (_listener, url) = silent_server();
+        let token = CancellationToken::new();
+        let mut opts = FetchOptions::default();
+        opts.cancel_token(token.clone());
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            token.cancel();
+        });
+
+        let html = fetch_html(&url, &opts).await;
+        assert!(matches!(html, Err(Error::Cancelled)));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking_fetch_html_timeout() {
+        let (_listener, url) = silent_server();
+        let mut opts = FetchOptions::default();
+        opts.timeout(Duration::from_millis(100));
+
+        let html = blocking::fetch_html(&url, &opts);
+        assert!(matches!(html, Err(Error::Timeout)));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking_fetch_html_deadline_passed() {
+        let (_listener, url) = silent_server();
+        let mut opts = FetchOptions::default();
+        opts.deadline(Instant::now());
+
+        let html = blocking::fetch_html(&url, &opts);
+        assert!(matches!(html, Err(Error::Timeout)));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking_repo() {
+        let data = blocking::repos::builder()
+            .programming_language("rust")
+            .since(Since::Weekly)
+            .get_data();
+
+        assert!(!data.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking_developers() {
+        let data = blocking::developers::builder()
+            .programming_language("rust")
+            .get_data();
+
+        assert!(!data.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn repo_cancelled_before_fetch() {
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let data = repos::builder().cancel_token(token).get_data().await;
+        assert!(matches!(data, Err(Error::Cancelled)));
+    }
+
     #[test]
     fn since_to_str() {
         let x: &str = Since::Daily.to_str();
@@ -123,6 +381,531 @@ mod tests {
         assert_eq!(x, Since::Daily);
     }
 
+    #[test]
+    fn since_traits() {
+        assert_eq!("Weekly".parse::<Since>().unwrap(), Since::Weekly);
+        assert!("yearly".parse::<Since>().is_err());
+        assert_eq!(Since::Monthly.to_string(), "monthly");
+        assert_eq!(Since::default(), Since::Daily);
+
+        let all: Vec<Since> = Since::all().collect();
+        assert_eq!(all, vec![Since::Daily, Since::Weekly, Since::Monthly]);
+
+        let map: std::collections::HashMap<Since, u32> = Since::all().map(|x| (x, 1)).collect();
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn since_serde() {
+        assert_eq!(json!(Since::Weekly), json!("weekly"));
+
+        let x: Since = serde_json::from_value(json!("monthly")).unwrap();
+        assert_eq!(x, Since::Monthly);
+
+        let x: Since = serde_json::from_value(json!("Weekly")).unwrap();
+        assert_eq!(x, Since::Weekly);
+        assert!(serde_json::from_value::<Since>(json!("hourly")).is_err());
+    }
+
+    #[test]
+    fn developers_sponsorable_url() {
+        let url = developers::builder()
+            .programming_language("rust")
+            .since(Since::Weekly)
+            .sponsorable(true)
+            .url();
+        assert_eq!(
+            url,
+            "https://github.com/trending/developers/rust?since=weekly&sponsorable=1"
+        );
+
+        let url = developers::builder().sponsorable(true).url();
+        assert_eq!(url, "https://github.com/trending/developers?sponsorable=1");
+    }
+
+    #[tokio::test]
+    async fn developers_sponsorable() {
+        let data = developers::builder()
+            .sponsorable(true)
+            .get_data()
+            .await
+            .unwrap();
+
+        assert!(data.iter().all(|x| x.sponsorable));
+    }
+
+    #[tokio::test]
+    async fn watch_events() {
+        use crate::watch::{Clock, Event, Watcher};
+        use futures_util::stream::StreamExt;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::{Arc, Mutex};
+
+        struct TestClock(Arc<Mutex<Vec<Duration>>>);
+
+        impl Clock for TestClock {
+            fn sleep(&mut self, d: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                self.0.lock().unwrap().push(d);
+                Box::pin(async {})
+            }
+        }
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let (a, b) = (data[0].clone(), data[1].clone());
+        let mut a2 = a.clone();
+        a2.stars = Some(25_400);
+        let mut c = a.clone();
+        c.url = Some("https://github.com/rust-lang/rust".to_string());
+
+        let mut polls = vec![
+            Ok(vec![a, b.clone()]),
+            Ok(vec![b, a2, c.clone()]),
+            Err(Error::Timeout),
+            Ok(vec![c]),
+        ]
+        .into_iter();
+        let sleeps = Arc::new(Mutex::new(Vec::new()));
+
+        let events = Watcher::new(move || std::future::ready(polls.next().unwrap()))
+            .interval(Duration::from_secs(60))
+            .clock(TestClock(sleeps.clone()))
+            .stream();
+        let events: Vec<_> = events.take(8).collect().await;
+
+        let url = |e: &Event<repos::Repository>| e.item().url.clone().unwrap();
+        let ok: Vec<String> = events
+            .iter()
+            .map(|e| match e {
+                Ok(e @ Event::Entered { rank, .. }) => format!("entered {} {}", rank, url(e)),
+                Ok(e @ Event::Left { rank, .. }) => format!("left {} {}", rank, url(e)),
+                Ok(e @ Event::RankChanged { from, to, .. }) => {
+                    format!("rank {}->{} {}", from, to, url(e))
+                }
+                Ok(Event::StarsChanged { from, to, .. }) => {
+                    format!("stars {:?}->{:?}", from, to)
+                }
+                Err(e) => format!("error {}", e),
+            })
+            .collect();
+
+        assert_eq!(
+            ok,
+            vec![
+                "rank 2->1 https://github.com/akane10/gtrend",
+                "rank 1->2 https://github.com/tokio-rs/tokio",
+                "stars Some(25314)->Some(25400)",
+                "entered 3 https://github.com/rust-lang/rust",
+                "error request timed out",
+                "rank 3->1 https://github.com/rust-lang/rust",
+                "left 1 https://github.com/akane10/gtrend",
+                "left 2 https://github.com/tokio-rs/tokio",
+            ]
+        );
+        assert_eq!(*sleeps.lock().unwrap(), vec![Duration::from_secs(60); 3]);
+    }
+
+    #[cfg(feature = "notify")]
+    #[tokio::test]
+    async fn notify_retry_and_signature() {
+        use crate::notify::{self, Notifier, Webhook, SIGNATURE_HEADER};
+        use crate::watch::Event;
+
+        assert_eq!(
+            notify::signature(b"key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let events = vec![
+            Event::Entered {
+                rank: 3,
+                item: data[0].clone(),
+            },
+            Event::Left {
+                rank: 1,
+                item: data[1].clone(),
+            },
+        ];
+
+        let (url, requests) = mock_server(vec![("*", 500, "", ""), ("*", 200, "", "")]).await;
+        let notifier = Notifier::new().webhook(
+            Webhook::new(url)
+                .secret("s3cret")
+                .backoff(Duration::from_millis(1)),
+        );
+        notifier.notify_repos(&events).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let (head, body) = requests[1].split_once("\r\n\r\n").unwrap();
+        let signature = format!(
+            "{}: {}",
+            SIGNATURE_HEADER.to_lowercase(),
+            notify::signature(b"s3cret", body.as_bytes())
+        );
+        assert!(head.to_lowercase().contains(&signature));
+
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["entries"].as_array().unwrap().len(), 1);
+        assert_eq!(body["entries"][0]["rank"], 3);
+        assert_eq!(body["entries"][0]["item"]["full_name"], "tokio-rs/tokio");
+    }
+
+    #[cfg(feature = "notify")]
+    #[tokio::test]
+    async fn notify_slack() {
+        use crate::notify::{Format, Notifier, Webhook};
+        use crate::watch::Event;
+
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        let events = vec![Event::Entered {
+            rank: 1,
+            item: data[0].clone(),
+        }];
+
+        let (url, requests) = mock_server(vec![("*", 400, "", "")]).await;
+        let notifier = Notifier::new().webhook(Webhook::new(url).format(Format::Slack));
+        assert!(notifier.notify_developers(&events).await.is_err());
+
+        // no retry on a client error
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let body: serde_json::Value =
+            serde_json::from_str(requests[0].split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(
+            body["text"],
+            "*New trending developers*\n• #1 <https://github.com/dtolnay|dtolnay> David Tolnay\n"
+        );
+
+        // nothing entered, nothing sent
+        let notifier = Notifier::new().webhook(Webhook::new("http://127.0.0.1:1"));
+        let left = vec![Event::Left {
+            rank: 1,
+            item: data[0].clone(),
+        }];
+        assert!(notifier.notify_developers(&left).await.is_ok());
+    }
+
+    #[cfg(feature = "enrich")]
+    #[tokio::test]
+    async fn enrich_repos() {
+        use crate::enrich::Client;
+
+        let tokio = r#"{
+            "full_name": "tokio-rs/tokio",
+            "topics": ["async", "rust"],
+            "license": { "spdx_id": "MIT", "name": "MIT License" },
+            "created_at": "2016-09-09T20:15:29Z",
+            "open_issues_count": 210,
+            "homepage": "https://tokio.rs",
+            "default_branch": "master"
+        }"#;
+        let (url, requests) = mock_server(vec![("/repos/tokio-rs/tokio", 200, "", tokio)]).await;
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let client = Client::new().api_url(url).token("t0ken").concurrency(2);
+        let enriched = client.enrich_repos(data.clone()).await.unwrap();
+
+        assert_eq!(enriched.len(), 2);
+        assert_eq!(
+            enriched[0].repository.full_name.as_deref(),
+            Some("tokio-rs/tokio")
+        );
+        let details = enriched[0].details.as_ref().unwrap();
+        assert_eq!(details.topics, vec!["async", "rust"]);
+        assert_eq!(details.license.as_deref(), Some("MIT"));
+        assert_eq!(details.open_issues, Some(210));
+        assert_eq!(details.default_branch.as_deref(), Some("master"));
+        // 404
+        assert!(enriched[1].details.is_none());
+
+        let json = serde_json::to_value(&enriched[0]).unwrap();
+        assert_eq!(json["full_name"], "tokio-rs/tokio");
+        assert_eq!(json["details"]["homepage"], "https://tokio.rs");
+
+        let recorded = requests.lock().unwrap().clone();
+        assert_eq!(recorded.len(), 2);
+        assert!(recorded
+            .iter()
+            .all(|x| x.to_lowercase().contains("authorization: bearer t0ken")));
+
+        // both answers, the 404 included, come from the cache now
+        client.enrich_repos(data).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[cfg(feature = "enrich")]
+    #[tokio::test]
+    async fn enrich_repos_graphql() {
+        use crate::enrich::{Backend, Client};
+
+        let response = r#"{
+            "data": {
+                "r0": {
+                    "repositoryTopics": { "nodes": [{ "topic": { "name": "async" } }] },
+                    "licenseInfo": { "spdxId": "MIT", "name": "MIT License" },
+                    "createdAt": "2016-09-09T20:15:29Z",
+                    "issues": { "totalCount": 150 },
+                    "pullRequests": { "totalCount": 30 },
+                    "homepageUrl": "https://tokio.rs",
+                    "defaultBranchRef": { "name": "master" }
+                },
+                "r1": null
+            },
+            "errors": [{ "type": "NOT_FOUND", "path": ["r1"], "message": "Could not resolve" }]
+        }"#;
+        let (url, requests) = mock_server(vec![("/graphql", 200, "", response)]).await;
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let client = Client::new()
+            .api_url(url.clone())
+            .token("t0ken")
+            .backend(Backend::GraphQl);
+        let enriched = client.enrich_repos(data.clone()).await.unwrap();
+
+        let details = enriched[0].details.as_ref().unwrap();
+        assert_eq!(details.topics, vec!["async"]);
+        // open issues plus open pull requests, as REST counts them
+        assert_eq!(details.open_issues, Some(180));
+        assert_eq!(details.default_branch.as_deref(), Some("master"));
+        assert!(enriched[1].details.is_none());
+
+        // one query for both, answered from the cache afterwards
+        client.enrich_repos(data.clone()).await.unwrap();
+        let recorded = requests.lock().unwrap().clone();
+        assert_eq!(recorded.len(), 1);
+        let body: serde_json::Value =
+            serde_json::from_str(recorded[0].split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert!(body["query"]
+            .as_str()
+            .unwrap()
+            .contains("r1: repository(owner: $o1, name: $n1)"));
+        assert_eq!(body["variables"]["o0"], "tokio-rs");
+        assert_eq!(body["variables"]["n1"], "gtrend");
+
+        // GraphQL needs a token, REST is used without one
+        let client = Client::new().api_url(url).backend(Backend::GraphQl);
+        let enriched = client.enrich_repos(data).await.unwrap();
+        assert!(enriched.iter().all(|x| x.details.is_none()));
+        let recorded = requests.lock().unwrap().clone();
+        assert!(recorded[1].starts_with("GET /repos/"));
+    }
+
+    #[cfg(feature = "enrich")]
+    #[tokio::test]
+    async fn enrich_developers() {
+        use crate::enrich::Client;
+
+        let dtolnay = r#"{
+            "login": "dtolnay",
+            "followers": 8000,
+            "public_repos": 150,
+            "company": null,
+            "location": "",
+            "bio": "Rust",
+            "blog": "https://dtolnay.github.io"
+        }"#;
+        // rate limited first, retried after `retry-after`
+        let (url, requests) = mock_server(vec![
+            ("/users/dtolnay", 429, "retry-after: 1\r\n", ""),
+            ("/users/dtolnay", 200, "", dtolnay),
+        ])
+        .await;
+
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        let client = Client::new()
+            .api_url(url)
+            .rate_limit_wait(Duration::from_secs(5));
+        let enriched = client.enrich_developers(data).await.unwrap();
+
+        let details = enriched[0].details.as_ref().unwrap();
+        assert_eq!(details.followers, Some(8000));
+        assert_eq!(details.location, None);
+        assert_eq!(details.blog.as_deref(), Some("https://dtolnay.github.io"));
+        assert!(enriched[1].details.is_none());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[cfg(feature = "enrich")]
+    #[tokio::test]
+    async fn enrich_rate_limited() {
+        use crate::enrich::Client;
+
+        let (url, requests) = mock_server(vec![(
+            "*",
+            403,
+            "x-ratelimit-remaining: 0\r\nx-ratelimit-reset: 4102444800\r\n",
+            "",
+        )])
+        .await;
+
+        let client = Client::new().api_url(url);
+        let res = client.user_details("dtolnay").await;
+        assert!(matches!(res, Err(Error::RateLimited(Some(_)))));
+
+        // fails without asking again until the reset
+        let res = client.repo_details("tokio-rs", "tokio").await;
+        assert!(matches!(res, Err(Error::RateLimited(Some(_)))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn server_languages() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let req = Request::get("/languages").body(Body::empty()).unwrap();
+        let resp = server::router().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = Request::get("/repositories?since=yearly")
+            .body(Body::empty())
+            .unwrap();
+        let resp = server::router().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = Request::get("/developers.atom?since=yearly")
+            .body(Body::empty())
+            .unwrap();
+        let resp = server::router().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // the params are accepted, only the invalid since is rejected as json
+        for sponsorable in ["1", "true", "TRUE", "0", "False", ""].iter() {
+            let req = Request::get(format!(
+                "/developers?sponsorable={}&since=yearly",
+                sponsorable
+            ))
+            .body(Body::empty())
+            .unwrap();
+            let resp = server::router().oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(
+                resp.headers()[axum::http::header::CONTENT_TYPE],
+                "application/json"
+            );
+        }
+
+        for sponsorable in ["yes", "0x1", "2"].iter() {
+            let req = Request::get(format!("/developers.rss?sponsorable={}", sponsorable))
+                .body(Body::empty())
+                .unwrap();
+            let resp = server::router().oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn query_to_url() {
+        let query = repos::builder()
+            .programming_language("C++")
+            .spoken_language("English")
+            .since(Since::Weekly)
+            .query()
+            .clone();
+
+        assert_eq!(query.language.as_deref(), Some("c++"));
+        assert_eq!(
+            query.to_url(),
+            "https://github.com/trending/c%2B%2B?since=weekly&spoken_language_code=en"
+        );
+        assert_eq!(
+            TrendingQuery::repositories().to_url(),
+            "https://github.com/trending"
+        );
+    }
+
+    #[test]
+    fn query_from_url() {
+        let query = TrendingQuery::from_url(
+            "https://github.com/trending/c%2B%2B?since=weekly&spoken_language_code=en",
+        )
+        .unwrap();
+        assert_eq!(query.kind, query::Kind::Repositories);
+        assert_eq!(query.language.as_deref(), Some("c++"));
+        assert_eq!(query.since, Some(Since::Weekly));
+        assert_eq!(query.spoken_language.as_deref(), Some("en"));
+
+        let query = TrendingQuery::from_url("https://github.com/trending/developers?sponsorable=1")
+            .unwrap();
+        assert_eq!(query.kind, query::Kind::Developers);
+        assert!(query.language.is_none());
+        assert!(query.sponsorable);
+        assert_eq!(TrendingQuery::from_url(&query.to_url()).unwrap(), query);
+
+        let query =
+            TrendingQuery::from_url("https://github.com/trending?since=&spoken_language_code=")
+                .unwrap();
+        assert!(query.since.is_none());
+        assert!(query.spoken_language.is_none());
+        assert_eq!(query.to_url(), "https://github.com/trending");
+
+        for url in &[
+            "https://github.com/rust-lang/rust",
+            "https://gitlab.com/trending",
+            "https://github.com/trending/rust/extra",
+            "https://github.com/trending?since=yearly",
+            "not a url",
+        ] {
+            assert!(matches!(
+                TrendingQuery::from_url(url),
+                Err(Error::InvalidUrl(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn builder_from_url() {
+        let builder = repos::Builder::from_url(
+            "https://github.com/trending/c%2B%2B?since=weekly&spoken_language_code=en",
+        )
+        .unwrap();
+        assert_eq!(
+            builder.url(),
+            "https://github.com/trending/c%2B%2B?since=weekly&spoken_language_code=en"
+        );
+
+        let builder = developers::Builder::from_url(
+            "https://github.com/trending/developers/Rust?since=daily",
+        )
+        .unwrap();
+        assert_eq!(builder.query().language.as_deref(), Some("rust"));
+        assert_eq!(builder.query().since, Some(Since::Daily));
+
+        assert!(matches!(
+            repos::Builder::from_url("https://github.com/trending/developers"),
+            Err(Error::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            developers::Builder::from_url("https://github.com/trending/rust"),
+            Err(Error::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            repos::Builder::from_url("https://github.com/akane10/gtrend"),
+            Err(Error::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn query_serde() {
+        let query = developers::builder()
+            .programming_language("rust")
+            .since(Since::Monthly)
+            .query()
+            .clone();
+        let value = json!(query);
+
+        assert_eq!(value["kind"], json!("developers"));
+        assert_eq!(value["since"], json!("monthly"));
+        assert_eq!(
+            serde_json::from_value::<TrendingQuery>(value).unwrap(),
+            query
+        );
+    }
+
     #[test]
     fn languages() {
         let data = languages::get_data();
@@ -392,3 +1175,706 @@ mod tests {
         assert_eq!(y.len(), data.len());
     }
 }
+
+// offline tests of everything built on the parsers, also run without a
+// network stack, e.g. with `--no-default-features --features parse`
+#[cfg(all(test, feature = "parse"))]
+mod parse_tests {
+    use super::*;
+    use crate::developers;
+    use crate::repos;
+    use std::time::Duration;
+
+    #[test]
+    fn repo_parse() {
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        assert_eq!(data.len(), 2);
+
+        let tokio = &data[0];
+        assert_eq!(tokio.author.as_deref(), Some("tokio-rs"));
+        assert_eq!(tokio.name.as_deref(), Some("tokio"));
+        assert_eq!(
+            tokio.url.as_deref(),
+            Some("https://github.com/tokio-rs/tokio")
+        );
+        assert_eq!(tokio.current_star, Some(1024));
+        assert_eq!(tokio.stars, Some(25314));
+        assert_eq!(tokio.forks, Some(2301));
+        assert_eq!(tokio.programming_language.as_deref(), Some("Rust"));
+        assert_eq!(tokio.lang_color.as_deref(), Some("#dea584"));
+        assert_eq!(tokio.built_by.len(), 2);
+        assert_eq!(tokio.built_by[0].username.as_deref(), Some("carllerche"));
+
+        assert!(data[1].description.is_none());
+        assert!(data[1].programming_language.is_none());
+    }
+
+    #[test]
+    fn repo_parse_metadata() {
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+
+        assert_eq!(data[0].full_name.as_deref(), Some("tokio-rs/tokio"));
+        assert!(data[0].sponsorable);
+        assert_eq!(data[0].since.as_deref(), Some("today"));
+
+        assert_eq!(data[1].full_name.as_deref(), Some("akane10/gtrend"));
+        assert!(!data[1].sponsorable);
+    }
+
+    #[test]
+    fn repo_deserialize_without_sponsorable() {
+        let x: repos::Repository = serde_json::from_value(json!({
+            "avatar": "https://github.com/akane10.png",
+            "author": "akane10",
+            "name": "gtrend",
+            "current_star": 10,
+            "description": null,
+            "programming_language": "Rust",
+            "url": "https://github.com/akane10/gtrend",
+            "stars": 100,
+            "forks": 5,
+            "lang_color": "#dea584",
+            "built_by": []
+        }))
+        .unwrap();
+
+        assert!(!x.sponsorable);
+        assert!(x.full_name.is_none());
+        assert!(x.since.is_none());
+    }
+
+    #[test]
+    fn developers_parse() {
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        assert_eq!(data.len(), 2);
+
+        let dtolnay = &data[0];
+        assert_eq!(dtolnay.name.as_deref(), Some("David Tolnay"));
+        assert_eq!(dtolnay.username.as_deref(), Some("dtolnay"));
+        assert_eq!(
+            dtolnay.sponsor_url.as_deref(),
+            Some("https://github.com/sponsors/dtolnay")
+        );
+
+        let repo = dtolnay.repo.as_ref().unwrap();
+        assert_eq!(repo.name.as_deref(), Some("cxx"));
+        assert_eq!(repo.url.as_deref(), Some("https://github.com/dtolnay/cxx"));
+
+        assert!(data[1].repo.is_none());
+        assert!(data[1].sponsor_url.is_none());
+    }
+
+    #[test]
+    fn developers_parse_sponsor_and_organization() {
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+
+        let dtolnay = &data[0];
+        assert!(dtolnay.sponsorable);
+        assert!(dtolnay.organization.is_none());
+        assert_eq!(
+            dtolnay.repo.as_ref().unwrap().label.as_deref(),
+            Some("Popular repo")
+        );
+
+        let octocat = &data[1];
+        assert!(!octocat.sponsorable);
+        assert_eq!(octocat.organization.as_deref(), Some("GitHub"));
+    }
+
+    #[test]
+    fn developer_deserialize_without_sponsorable() {
+        let x: developers::Developer = serde_json::from_value(json!({
+            "name": "David Tolnay",
+            "username": "dtolnay",
+            "url": "https://github.com/dtolnay",
+            "sponsor_url": null,
+            "avatar": "https://github.com/dtolnay.png",
+            "repo": null
+        }))
+        .unwrap();
+        assert!(!x.sponsorable);
+        assert!(x.organization.is_none());
+
+        let x: developers::Developer = serde_json::from_value(json!(x)).unwrap();
+        assert!(!x.sponsorable);
+        assert_eq!(x.username.as_deref(), Some("dtolnay"));
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_repos() {
+        use crate::export::csv::{self, RepositoryColumn};
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let columns = [
+            RepositoryColumn::FullName,
+            RepositoryColumn::CurrentStar,
+            RepositoryColumn::Description,
+            RepositoryColumn::BuiltBy,
+        ];
+        let out = csv::repos_to_string(&data, &columns).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], "full_name,current_star,description,built_by");
+        assert!(lines[1].starts_with("tokio-rs/tokio,1024,"));
+        assert!(lines[1].ends_with(",carllerche;Darksonn"));
+        assert_eq!(lines[2], "akane10/gtrend,12,,");
+
+        let all = csv::repos_to_string(&data, &[]).unwrap();
+        let header = all.lines().next().unwrap();
+        assert_eq!(header.split(',').count(), RepositoryColumn::all().len());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_developers() {
+        use crate::export::csv::{self, DeveloperColumn};
+
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        let columns: Vec<DeveloperColumn> = "username,repo_name,sponsorable"
+            .split(',')
+            .map(|x| x.parse().unwrap())
+            .collect();
+        let out = csv::developers_to_string(&data, &columns).unwrap();
+
+        assert_eq!(
+            out,
+            "username,repo_name,sponsorable\ndtolnay,cxx,true\noctocat,,false\n"
+        );
+        assert!("stars".parse::<DeveloperColumn>().is_err());
+    }
+
+    #[test]
+    fn markdown_repos() {
+        use crate::export::markdown;
+
+        let mut data = repos::parse(include_str!("../fixtures/repos.html"));
+        data[1].description = Some("a | b".to_string());
+        let out = markdown::render_repos(&data, &markdown::repos_table());
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("| 1 | [tokio-rs/tokio](https://github.com/tokio-rs/tokio) |"));
+        assert_eq!(
+            lines[3],
+            "| 2 | [akane10/gtrend](https://github.com/akane10/gtrend) | a \\| b |  | 87 | 5 | 12 |"
+        );
+    }
+
+    #[test]
+    fn markdown_custom_template() {
+        use crate::export::{markdown, Template};
+
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        let template = Template::new(
+            "## Developers\n",
+            "- {username} {unknown} {repo_name}\n",
+            "",
+        );
+
+        assert_eq!(
+            markdown::render_developers(&data, &template),
+            "## Developers\n- dtolnay {unknown} cxx\n- octocat {unknown} \n"
+        );
+    }
+
+    #[test]
+    fn html_repos() {
+        use crate::export::html;
+
+        let mut data = repos::parse(include_str!("../fixtures/repos.html"));
+        data[1].description = Some("<script>".to_string());
+        let out = html::render_repos(&data, &html::repos_page());
+
+        assert!(out.starts_with("<!DOCTYPE html>"));
+        assert!(out.contains(".swatch { display: inline-block;"));
+        assert!(out.contains("src=\"https://github.com/tokio-rs.png\""));
+        assert!(out.contains("style=\"background-color: #dea584\""));
+        assert!(out.contains("<a href=\"https://github.com/tokio-rs/tokio\">tokio-rs/tokio</a>"));
+        assert!(out.contains("&lt;script&gt;"));
+        assert!(out.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn ndjson_repos() {
+        use crate::export::ndjson;
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let out = ndjson::repos_to_string(&data).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(out.ends_with('\n'));
+        let first: repos::Repository = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.full_name.as_deref(), Some("tokio-rs/tokio"));
+    }
+
+    #[test]
+    fn ndjson_writer_query() {
+        use crate::export::ndjson::Writer;
+
+        let repos_data = repos::parse(include_str!("../fixtures/repos.html"));
+        let dev_data = developers::parse(include_str!("../fixtures/developers.html"));
+        let query = TrendingQuery {
+            language: Some("rust".to_string()),
+            ..TrendingQuery::repositories()
+        };
+
+        let mut wtr = Writer::new(Vec::new());
+        wtr.write_query(&query, &repos_data).unwrap();
+        wtr.write_query(&TrendingQuery::developers(), &dev_data)
+            .unwrap();
+        let out = String::from_utf8(wtr.into_inner().unwrap()).unwrap();
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|x| serde_json::from_str(x).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["query"]["language"], "rust");
+        assert_eq!(lines[0]["full_name"], "tokio-rs/tokio");
+        assert_eq!(lines[2]["query"]["kind"], "developers");
+        assert_eq!(lines[2]["username"], "dtolnay");
+    }
+
+    #[test]
+    fn schema_compat() {
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let json = Schema::GithubTrendingApiCompat.repos_json(&data);
+
+        assert_eq!(json[0]["currentPeriodStars"], 1024);
+        assert_eq!(json[0]["languageColor"], "#dea584");
+        assert_eq!(json[0]["language"], "Rust");
+        assert_eq!(json[0]["builtBy"][1]["username"], "Darksonn");
+        assert!(json[0].get("current_star").is_none());
+
+        let json = Schema::V1Snake.repos_json(&data);
+        assert_eq!(json[0]["current_star"], 1024);
+
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        let json = Schema::GithubTrendingApiCompat.developers_json(&data);
+        assert_eq!(json[0]["sponsorUrl"], "https://github.com/sponsors/dtolnay");
+        assert_eq!(json[0]["repo"]["name"], "cxx");
+        assert_eq!(json[1]["repo"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn schema_json_schema() {
+        let repo = repos::parse(include_str!("../fixtures/repos.html")).remove(0);
+        let dev = developers::parse(include_str!("../fixtures/developers.html")).remove(0);
+
+        // every documented property is emitted and nothing else
+        let keys = |v: serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<String> = v.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        for schema in Schema::all() {
+            let doc: serde_json::Value =
+                serde_json::from_str(schema.repository_json_schema()).unwrap();
+            assert_eq!(
+                keys(doc["properties"].clone()),
+                keys(schema.repository(&repo))
+            );
+
+            let doc: serde_json::Value =
+                serde_json::from_str(schema.developer_json_schema()).unwrap();
+            assert_eq!(
+                keys(doc["properties"].clone()),
+                keys(schema.developer(&dev))
+            );
+        }
+
+        assert_eq!(
+            "github-trending-api-compat".parse::<Schema>().unwrap(),
+            Schema::GithubTrendingApiCompat
+        );
+        assert!("v2".parse::<Schema>().is_err());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn arrow_batches_and_parquet() {
+        use crate::export::arrow::{self, Writer};
+        use crate::momentum::Snapshot;
+        use ::arrow::array::{Array, AsArray};
+        use ::arrow::datatypes::{TimestampMillisecondType, UInt32Type};
+        use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use std::time::UNIX_EPOCH;
+
+        let repos_data = repos::parse(include_str!("../fixtures/repos.html"));
+        let developers_data = developers::parse(include_str!("../fixtures/developers.html"));
+
+        let batch = arrow::repos_to_batch(&repos_data).unwrap();
+        assert_eq!(batch.num_rows(), repos_data.len());
+        let full_name = batch
+            .column_by_name("full_name")
+            .unwrap()
+            .as_string::<i32>();
+        assert_eq!(Some(full_name.value(0)), repos_data[0].full_name.as_deref());
+        let built_by = batch.column_by_name("built_by").unwrap().as_list::<i32>();
+        let first = built_by.value(0);
+        let usernames = first.as_struct().column(0).as_string::<i32>();
+        let expected: Vec<Option<&str>> = repos_data[0]
+            .built_by
+            .iter()
+            .map(|x| x.username.as_deref())
+            .collect();
+        assert_eq!(usernames.iter().collect::<Vec<_>>(), expected);
+
+        let batch = arrow::developers_to_batch(&developers_data).unwrap();
+        let repo = batch.column_by_name("repo").unwrap().as_struct();
+        for (i, x) in developers_data.iter().enumerate() {
+            assert_eq!(repo.is_valid(i), x.repo.is_some());
+        }
+        assert!(developers_data.iter().any(|x| x.repo.is_none()));
+
+        let snapshot = Snapshot {
+            taken_at: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+            data: repos_data.clone(),
+        };
+        let batch = arrow::snapshot_to_batch(&snapshot).unwrap();
+        let taken_at = batch.column(0).as_primitive::<TimestampMillisecondType>();
+        assert_eq!(taken_at.value(1), 1_700_000_000_123);
+        let rank = batch.column(1).as_primitive::<UInt32Type>();
+        assert_eq!(rank.values().to_vec(), vec![1, 2]);
+
+        // two snapshots appended to one file
+        let path = std::env::temp_dir().join(format!("gtrend-{}.parquet", std::process::id()));
+        let mut wtr = Writer::snapshots(std::fs::File::create(&path).unwrap()).unwrap();
+        wtr.write_snapshot(&snapshot).unwrap();
+        wtr.write_snapshot(&Snapshot::new(repos_data[..1].to_vec()))
+            .unwrap();
+        assert!(wtr.write_repos(&repos_data).is_err());
+        wtr.into_inner().unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(|x| x.unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches[0].schema(), arrow::snapshots_schema());
+        let read = ::arrow::compute::concat_batches(&arrow::snapshots_schema(), &batches).unwrap();
+        assert_eq!(read.num_rows(), repos_data.len() + 1);
+        assert_eq!(
+            read.column_by_name("built_by").unwrap(),
+            &::arrow::compute::concat(&[
+                batch.column_by_name("built_by").unwrap().as_ref(),
+                arrow::repos_to_batch(&repos_data[..1])
+                    .unwrap()
+                    .column_by_name("built_by")
+                    .unwrap()
+                    .as_ref(),
+            ])
+            .unwrap()
+        );
+
+        let mut buf = Vec::new();
+        arrow::write_developers(&mut buf, &developers_data).unwrap();
+        assert!(buf.starts_with(b"PAR1") && buf.ends_with(b"PAR1"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_store() {
+        use crate::stats::Count;
+        use crate::storage::sqlite::{self, Store};
+        use std::time::UNIX_EPOCH;
+
+        let repos_data = repos::parse(include_str!("../fixtures/repos.html"));
+        let developers_data = developers::parse(include_str!("../fixtures/developers.html"));
+        let query = TrendingQuery {
+            language: Some("rust".to_string()),
+            since: Some(Since::Weekly),
+            ..TrendingQuery::repositories()
+        };
+        let day = |n: u64| UNIX_EPOCH + Duration::from_secs(1_700_000_000 + n * 86_400);
+
+        let mut store = Store::open_in_memory().unwrap();
+        let first = store.insert_repos(&query, day(0), &repos_data).unwrap();
+        let mut later = repos_data[..1].to_vec();
+        later[0].stars = Some(30_000);
+        let second = store.insert_repos(&query, day(1), &later).unwrap();
+        store
+            .insert_repos(&TrendingQuery::repositories(), day(2), &repos_data[1..])
+            .unwrap();
+        let devs = store
+            .insert_developers(&TrendingQuery::developers(), day(2), &developers_data)
+            .unwrap();
+
+        let fetches = store.fetches().unwrap();
+        assert_eq!(fetches.len(), 4);
+        assert_eq!(fetches[0].query, query);
+        assert_eq!(fetches[0].fetched_at, day(0));
+        assert_eq!(
+            store.fetch(devs).unwrap().unwrap().query,
+            TrendingQuery::developers()
+        );
+        assert!(store.fetch(100).unwrap().is_none());
+
+        let stored = store.repos(first).unwrap();
+        assert_eq!(
+            serde_json::to_value(&stored).unwrap(),
+            serde_json::to_value(&repos_data).unwrap()
+        );
+        assert!(!stored[0].built_by.is_empty());
+        let stored = store.developers(devs).unwrap();
+        assert_eq!(
+            serde_json::to_value(&stored).unwrap(),
+            serde_json::to_value(&developers_data).unwrap()
+        );
+
+        let full_name = repos_data[0].full_name.as_deref().unwrap();
+        let appearances = store.repo_appearances(full_name).unwrap();
+        let ids: Vec<(i64, usize, Option<u32>)> = appearances
+            .iter()
+            .map(|x| (x.fetch.id, x.rank, x.stars))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![(first, 1, repos_data[0].stars), (second, 1, Some(30_000))]
+        );
+        let url = repos_data[1].url.as_deref().unwrap();
+        assert_eq!(store.repo_appearances(url).unwrap().len(), 2);
+
+        // tokio is in two fetches but counted once
+        let count = |name: &str, count| Count {
+            name: name.to_string(),
+            count,
+        };
+        assert_eq!(store.top_languages(day(0)).unwrap(), vec![count("Rust", 1)]);
+        assert_eq!(store.top_languages(day(2)).unwrap(), vec![]);
+
+        let snapshots = store.snapshots(&query).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[1].taken_at, day(1));
+        assert_eq!(snapshots[1].data[0].stars, Some(30_000));
+        assert_eq!(
+            store.snapshots(&TrendingQuery::repositories()).unwrap()[0]
+                .data
+                .len(),
+            repos_data.len() - 1
+        );
+
+        // opening again leaves a migrated database as it is
+        let dir = std::env::temp_dir().join(format!("gtrend-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&dir);
+        Store::open(&dir)
+            .unwrap()
+            .insert_repos(&query, day(0), &repos_data)
+            .unwrap();
+        let store = Store::open(&dir).unwrap();
+        assert_eq!(store.fetches().unwrap().len(), 1);
+        let version: usize = store
+            .connection()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 1);
+        drop(store);
+        std::fs::remove_file(&dir).unwrap();
+
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 99).unwrap();
+        assert!(sqlite::migrate(&mut conn).is_err());
+    }
+
+    #[test]
+    fn momentum_rank() {
+        use crate::momentum::{self, Snapshot};
+        use std::time::UNIX_EPOCH;
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let (tokio, gtrend) = (data[0].clone(), data[1].clone());
+        let with_stars = |stars: u32| {
+            let mut x = tokio.clone();
+            x.stars = Some(stars);
+            x
+        };
+        let mut gone = tokio.clone();
+        gone.url = Some("https://github.com/rust-lang/rust".to_string());
+
+        let day = |n: u64| UNIX_EPOCH + Duration::from_secs(1_700_000_000 + n * 86_400);
+        let snapshots = vec![
+            Snapshot {
+                taken_at: day(2),
+                data: vec![gtrend, with_stars(25_300)],
+            },
+            Snapshot {
+                taken_at: day(0),
+                data: vec![with_stars(25_000), gone],
+            },
+            Snapshot {
+                taken_at: day(1),
+                data: vec![with_stars(25_100)],
+            },
+        ];
+
+        let ranked = momentum::rank(&snapshots);
+        assert_eq!(ranked.len(), 2);
+
+        let top = &ranked[0];
+        assert_eq!(top.repository.full_name.as_deref(), Some("tokio-rs/tokio"));
+        assert_eq!(top.repository.stars, Some(25_300));
+        assert!((top.metrics.stars_per_day - 150.0).abs() < 1e-9);
+        assert!((top.metrics.acceleration - 100.0).abs() < 1e-9);
+        assert_eq!(top.metrics.days_on_list, 3);
+        assert!((top.metrics.momentum - 850.0 / 3f64.sqrt()).abs() < 1e-9);
+
+        // only in one snapshot, stars today
+        let newcomer = &ranked[1];
+        assert_eq!(newcomer.metrics.stars_per_day, 12.0);
+        assert_eq!(newcomer.metrics.acceleration, 0.0);
+        assert_eq!(newcomer.metrics.days_on_list, 1);
+
+        let json = serde_json::to_value(newcomer).unwrap();
+        assert_eq!(json["full_name"], "akane10/gtrend");
+        assert_eq!(json["metrics"]["momentum"], 12.0);
+
+        assert!(momentum::rank(&[]).is_empty());
+    }
+
+    #[test]
+    fn stats_summarize() {
+        use crate::stats::{self, Count};
+
+        let mut data = repos::parse(include_str!("../fixtures/repos.html"));
+        let mut other = data[0].clone();
+        other.author = Some("akane10".to_string());
+        other.current_star = Some(100);
+        data.push(other);
+
+        let summary = stats::summarize(&data);
+        let count = |name: &str, count| Count {
+            name: name.to_string(),
+            count,
+        };
+
+        assert_eq!(summary.repositories, 3);
+        assert_eq!(summary.languages, vec![count("Rust", 2)]);
+        assert_eq!(summary.without_language, 1);
+        assert_eq!(summary.total_current_stars, 1024 + 12 + 100);
+        assert_eq!(summary.median_current_stars, Some(100.0));
+        assert_eq!(
+            summary.authors,
+            vec![count("akane10", 2), count("tokio-rs", 1)]
+        );
+        assert_eq!(
+            summary.contributors,
+            vec![count("Darksonn", 2), count("carllerche", 2)]
+        );
+
+        assert_eq!(stats::summarize(&[]).median_current_stars, None);
+        assert_eq!(
+            stats::summarize(&data[..2]).median_current_stars,
+            Some(518.0)
+        );
+    }
+
+    #[test]
+    fn periods_classify() {
+        use crate::periods::{self, Class, Ranks};
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let (a, b) = (data[0].clone(), data[1].clone());
+        let mut c = a.clone();
+        c.url = Some("https://github.com/akane10/c".to_string());
+
+        let daily = vec![a.clone(), b.clone()];
+        let weekly = vec![c.clone(), b.clone(), a.clone()];
+        let monthly = vec![a.clone()];
+        let classified = periods::classify(&daily, &weekly, &monthly);
+
+        let rows: Vec<(Option<&str>, Ranks, Option<Class>)> = classified
+            .iter()
+            .map(|x| (x.repository.url.as_deref(), x.ranks, x.class))
+            .collect();
+        let ranks = |daily, weekly, monthly| Ranks {
+            daily,
+            weekly,
+            monthly,
+        };
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    a.url.as_deref(),
+                    ranks(Some(1), Some(3), Some(1)),
+                    Some(Class::Sustained)
+                ),
+                (
+                    b.url.as_deref(),
+                    ranks(Some(2), Some(2), None),
+                    Some(Class::Rising)
+                ),
+                (c.url.as_deref(), ranks(None, Some(1), None), None),
+            ]
+        );
+
+        let classified = periods::classify(&daily, &[], &[]);
+        assert!(classified.iter().all(|x| x.class == Some(Class::Flash)));
+
+        let json = serde_json::to_value(&classified[0]).unwrap();
+        assert_eq!(json["class"], "flash");
+        assert_eq!(json["ranks"]["daily"], 1);
+        assert_eq!(classified[1].ranks.get(Since::Daily), Some(2));
+        assert_eq!(json["full_name"], a.full_name.as_deref().unwrap());
+    }
+
+    #[test]
+    fn feed_repos() {
+        use crate::export::feed::Feed;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let data = repos::parse(include_str!("../fixtures/repos.html"));
+        let query = TrendingQuery {
+            language: Some("rust".to_string()),
+            since: Some(Since::Weekly),
+            ..TrendingQuery::repositories()
+        };
+        let mut feed = Feed::new(&query);
+        feed.updated = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(feed.title, "Trending rust repositories this week");
+
+        let rss = feed.repos_rss(&data);
+        assert!(rss.contains("<link>https://github.com/trending/rust?since=weekly</link>"));
+        assert!(rss.contains("<lastBuildDate>Tue, 14 Nov 2023 22:13:20 +0000</lastBuildDate>"));
+        assert!(rss.contains("<guid isPermaLink=\"true\">https://github.com/tokio-rs/tokio</guid>"));
+        assert!(rss.contains("<category>Rust</category>"));
+        assert_eq!(rss.matches("<item>").count(), 2);
+
+        let atom = feed.repos_atom(&data);
+        assert!(atom.contains("<updated>2023-11-14T22:13:20Z</updated>"));
+        assert_eq!(atom.matches("<updated>").count(), 1 + 2);
+        assert!(atom.contains("<id>https://github.com/akane10/gtrend</id>"));
+        assert_eq!(atom.matches("<entry>").count(), 2);
+    }
+
+    #[test]
+    fn feed_developers() {
+        use crate::export::feed::Feed;
+
+        let data = developers::parse(include_str!("../fixtures/developers.html"));
+        let feed = Feed::new(&TrendingQuery::developers());
+        assert_eq!(feed.title, "Trending developers today");
+
+        let rss = feed.developers_rss(&data);
+        assert!(rss.contains("<guid isPermaLink=\"true\">https://github.com/dtolnay</guid>"));
+        assert!(rss.contains("<title>David Tolnay (dtolnay)</title>"));
+        assert!(rss.contains("<description>cxx: "));
+
+        let atom = feed.developers_atom(&data);
+        let entries = atom.matches("<entry>").count();
+        assert!(entries > 0);
+        assert_eq!(atom.matches("<updated>").count(), 1 + entries);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn wasm_parse_repos() {
+        let html = include_str!("../fixtures/repos.html");
+        let data: Value = serde_json::from_str(&wasm::parse_repos(html).unwrap()).unwrap();
+
+        assert_eq!(data, json!(repos::parse(html)));
+    }
+}
@@ -0,0 +1,159 @@
+//! Star velocity of trending repositories over stored snapshots.
+//!
+//! ```no_run
+//! use gtrend::momentum::{self, Snapshot};
+//! # async fn run(mut stored: Vec<Snapshot>) -> Result<(), gtrend::Error> {
+//! stored.push(Snapshot::new(gtrend::repos::builder().get_data().await?));
+//!
+//! for x in momentum::rank(&stored).iter().take(5) {
+//!     println!("{:?} {:.1}", x.repository.full_name, x.metrics.momentum);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::repos::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY: f64 = 86_400.0;
+
+/// One trending result and when it was fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: SystemTime,
+    pub data: Vec<Repository>,
+}
+
+impl Snapshot {
+    pub fn new(data: Vec<Repository>) -> Self {
+        Snapshot {
+            taken_at: SystemTime::now(),
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    /// Total stars gained per day between the first and last snapshot the
+    /// repository is in, or `current_star` over its period when it is in
+    /// only one.
+    pub stars_per_day: f64,
+    /// Change of `stars_per_day` per day over the last three snapshots,
+    /// 0 with fewer.
+    pub acceleration: f64,
+    /// Distinct days (UTC) with a snapshot listing the repository.
+    pub days_on_list: u32,
+    /// `stars_per_day + 7 * acceleration`, the velocity projected a week
+    /// ahead, at least 0 and divided by `sqrt(days_on_list)` so newcomers
+    /// rank above repositories that have been trending for a while.
+    pub momentum: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredRepository {
+    #[serde(flatten)]
+    pub repository: Repository,
+    pub metrics: Metrics,
+}
+
+fn secs(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs_f64())
+        .unwrap_or_default()
+}
+
+fn period_days(since: Option<&str>) -> f64 {
+    match since {
+        Some("this week") => 7.0,
+        Some("this month") => 30.0,
+        _ => 1.0,
+    }
+}
+
+/// Metrics of one repository from its `(taken_at, repository)` appearances,
+/// oldest first.
+pub fn metrics(appearances: &[(SystemTime, &Repository)]) -> Metrics {
+    let days_on_list = appearances
+        .iter()
+        .map(|(t, _)| (secs(*t) / DAY) as u64)
+        .collect::<HashSet<_>>()
+        .len() as u32;
+
+    // (days, total stars)
+    let points: Vec<(f64, f64)> = appearances
+        .iter()
+        .filter_map(|(t, x)| x.stars.map(|s| (secs(*t) / DAY, f64::from(s))))
+        .collect();
+    let rate = |a: (f64, f64), b: (f64, f64)| (b.1 - a.1) / (b.0 - a.0);
+
+    let stars_per_day = match (points.first(), points.last()) {
+        (Some(&first), Some(&last)) if last.0 > first.0 => rate(first, last),
+        _ => appearances
+            .last()
+            .map(|(_, x)| f64::from(x.current_star.unwrap_or(0)) / period_days(x.since.as_deref()))
+            .unwrap_or_default(),
+    };
+
+    let acceleration = match points.len() {
+        n if n >= 3 => {
+            let (a, b, c) = (points[n - 3], points[n - 2], points[n - 1]);
+            if a.0 < b.0 && b.0 < c.0 {
+                (rate(b, c) - rate(a, b)) / ((c.0 - a.0) / 2.0)
+            } else {
+                0.0
+            }
+        }
+        _ => 0.0,
+    };
+
+    let momentum =
+        (stars_per_day + 7.0 * acceleration).max(0.0) / f64::from(days_on_list.max(1)).sqrt();
+
+    Metrics {
+        stars_per_day,
+        acceleration,
+        days_on_list,
+        momentum,
+    }
+}
+
+/// The repositories of the latest snapshot, highest momentum first.
+/// Repositories are matched across snapshots by url.
+pub fn rank(snapshots: &[Snapshot]) -> Vec<ScoredRepository> {
+    let mut snapshots: Vec<&Snapshot> = snapshots.iter().collect();
+    snapshots.sort_by_key(|x| x.taken_at);
+    let latest = match snapshots.last() {
+        Some(latest) => latest,
+        None => return Vec::new(),
+    };
+
+    let mut appearances: HashMap<&str, Vec<(SystemTime, &Repository)>> = HashMap::new();
+    for snapshot in &snapshots {
+        for x in &snapshot.data {
+            if let Some(url) = x.url.as_deref() {
+                appearances
+                    .entry(url)
+                    .or_default()
+                    .push((snapshot.taken_at, x));
+            }
+        }
+    }
+
+    let mut scored: Vec<ScoredRepository> = latest
+        .data
+        .iter()
+        .filter_map(|x| {
+            let seen = appearances.get(x.url.as_deref()?)?;
+            Some(ScoredRepository {
+                repository: x.clone(),
+                metrics: metrics(seen),
+            })
+        })
+        .collect();
+    scored.sort_by(|a, b| b.metrics.momentum.total_cmp(&a.metrics.momentum));
+
+    scored
+}
@@ -0,0 +1,329 @@
+//! POST new trending entries to webhooks, enabled by the `notify` feature.
+//!
+//! Only `Event::Entered` is sent; the other watch events are ignored. A
+//! webhook with a secret gets an `X-Gtrend-Signature: sha256=<hex>` header,
+//! the HMAC-SHA256 of the body, like GitHub's `X-Hub-Signature-256`.
+//!
+//! ```no_run
+//! use futures_util::stream::StreamExt;
+//! use gtrend::notify::{Format, Notifier, Webhook};
+//! use gtrend::watch;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let notifier = Notifier::new()
+//!     .webhook(Webhook::new("https://hooks.slack.com/services/...").format(Format::Slack))
+//!     .webhook(Webhook::new("https://bot.example.com/trending").secret("s3cret"));
+//!
+//! let events = watch::repos(gtrend::repos::builder().programming_language("rust")).stream();
+//! futures_util::pin_mut!(events);
+//! while let Some(event) = events.next().await {
+//!     notifier.notify_repos(&[event?]).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::developers::Developer;
+use crate::error::Error;
+use crate::export::{markdown, DeveloperColumn, RepositoryColumn, Template};
+use crate::repos::Repository;
+use crate::watch::Event;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-Gtrend-Signature";
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum Format {
+    /// `{"entries": [{"rank": 1, "item": {...}}]}` with the serialized items.
+    #[default]
+    Json,
+    /// Incoming webhook `{"text": ...}`.
+    Slack,
+    /// `{"content": ...}`.
+    Discord,
+    /// Office 365 connector `MessageCard`.
+    Teams,
+}
+
+impl Format {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Slack => "slack",
+            Self::Discord => "discord",
+            Self::Teams => "teams",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Self::Json, Self::Slack, Self::Discord, Self::Teams]
+            .iter()
+            .copied()
+            .find(|x| x.to_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                Error::Others(format!(
+                    "expected json, slack, discord or teams, got `{}`",
+                    s
+                ))
+            })
+    }
+}
+
+#[derive(Clone)]
+pub struct Webhook {
+    url: String,
+    format: Format,
+    template: Option<Template>,
+    secret: Option<Vec<u8>>,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl Webhook {
+    pub fn new<T: Into<String>>(url: T) -> Self {
+        Webhook {
+            url: url.into(),
+            format: Format::default(),
+            template: None,
+            secret: None,
+            retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Message text of the chat formats; `{rank}` is the rank the entry came
+    /// in at. Ignored by `Format::Json`.
+    pub fn template(mut self, template: Template) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub fn secret<T: AsRef<[u8]>>(mut self, secret: T) -> Self {
+        self.secret = Some(secret.as_ref().to_vec());
+        self
+    }
+
+    /// Attempts after the first one on a connection error, 429 or 5xx.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Wait before the first retry, doubled for every further one.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    fn text(
+        &self,
+        default: fn(Format) -> Template,
+        render: impl Fn(&Template) -> String,
+    ) -> String {
+        match self.template.as_ref() {
+            Some(template) => render(template),
+            None => render(&default(self.format)),
+        }
+    }
+
+    fn payload(&self, entries: Value, text: String) -> Value {
+        match self.format {
+            Format::Json => json!({ "entries": entries }),
+            Format::Slack => json!({ "text": text }),
+            Format::Discord => json!({ "content": text }),
+            Format::Teams => json!({
+                "@type": "MessageCard",
+                "@context": "https://schema.org/extensions",
+                "summary": text.lines().next().unwrap_or_default(),
+                "text": text,
+            }),
+        }
+    }
+
+    async fn send(&self, client: &reqwest::Client, body: Vec<u8>) -> Result<(), Error> {
+        let mut attempt = 0;
+        loop {
+            let mut req = client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(secret) = self.secret.as_ref() {
+                req = req.header(SIGNATURE_HEADER, signature(secret, &body));
+            }
+
+            let retry = match req.send().await {
+                Ok(res) if res.status().is_success() => return Ok(()),
+                Ok(res) => {
+                    let status = res.status();
+                    let err = Error::Others(format!("webhook {} responded {}", self.url, status));
+                    if status.is_server_error() || status.as_u16() == 429 {
+                        err
+                    } else {
+                        return Err(err);
+                    }
+                }
+                Err(e) => Error::from(e),
+            };
+
+            if attempt >= self.retries {
+                return Err(retry);
+            }
+            tokio::time::sleep(self.backoff * 2u32.saturating_pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `body`.
+pub fn signature(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("hmac takes keys of any size");
+    mac.update(body);
+
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+#[derive(Serialize)]
+struct Entry<'a, T> {
+    rank: usize,
+    item: &'a T,
+}
+
+fn entered<T>(events: &[Event<T>]) -> Vec<(usize, &T)> {
+    events
+        .iter()
+        .filter_map(|x| match x {
+            Event::Entered { rank, item } => Some((*rank, item)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn slack_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape(format: Format) -> fn(&str) -> String {
+    match format {
+        Format::Slack => slack_escape,
+        _ => markdown::escape,
+    }
+}
+
+fn repos_template(format: Format) -> Template {
+    match format {
+        Format::Slack => Template::new(
+            "*New trending repositories*\n",
+            "• #{rank} <{url}|{full_name}> {description}\n",
+            "",
+        ),
+        _ => Template::new(
+            "**New trending repositories**\n",
+            "- #{rank} [{full_name}]({url}) {description}\n",
+            "",
+        ),
+    }
+}
+
+fn developers_template(format: Format) -> Template {
+    match format {
+        Format::Slack => Template::new(
+            "*New trending developers*\n",
+            "• #{rank} <{url}|{username}> {name}\n",
+            "",
+        ),
+        _ => Template::new(
+            "**New trending developers**\n",
+            "- #{rank} [{username}]({url}) {name}\n",
+            "",
+        ),
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Notifier {
+    client: reqwest::Client,
+    webhooks: Vec<Webhook>,
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        Notifier::default()
+    }
+
+    pub fn webhook(mut self, webhook: Webhook) -> Self {
+        self.webhooks.push(webhook);
+        self
+    }
+
+    /// POST the entered repositories to every webhook. Nothing is sent
+    /// without any; all webhooks are tried and the first error is returned.
+    pub async fn notify_repos(&self, events: &[Event<Repository>]) -> Result<(), Error> {
+        let entries = entered(events);
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut result = Ok(());
+        for webhook in &self.webhooks {
+            let text = webhook.text(repos_template, |t| {
+                t.render_ranked::<_, RepositoryColumn>(&entries, escape(webhook.format))
+            });
+            let sent = self.post(webhook, &entries, text).await;
+            result = result.and(sent);
+        }
+        result
+    }
+
+    pub async fn notify_developers(&self, events: &[Event<Developer>]) -> Result<(), Error> {
+        let entries = entered(events);
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut result = Ok(());
+        for webhook in &self.webhooks {
+            let text = webhook.text(developers_template, |t| {
+                t.render_ranked::<_, DeveloperColumn>(&entries, escape(webhook.format))
+            });
+            let sent = self.post(webhook, &entries, text).await;
+            result = result.and(sent);
+        }
+        result
+    }
+
+    async fn post<T: Serialize>(
+        &self,
+        webhook: &Webhook,
+        entries: &[(usize, &T)],
+        text: String,
+    ) -> Result<(), Error> {
+        let entries: Vec<Entry<T>> = entries
+            .iter()
+            .map(|&(rank, item)| Entry { rank, item })
+            .collect();
+        let body = serde_json::to_vec(&webhook.payload(json!(entries), text))?;
+
+        webhook.send(&self.client, body).await
+    }
+}
@@ -0,0 +1,131 @@
+//! Which repositories trend today only, and which this week or month too.
+//!
+//! ```no_run
+//! use gtrend::periods::{self, Class};
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let builder = gtrend::repos::builder().programming_language("rust");
+//!
+//! for x in periods::compare(&builder).await? {
+//!     if x.class == Some(Class::Sustained) {
+//!         println!("{:?} {:?}", x.repository.full_name, x.ranks);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::repos::Repository;
+use crate::Since;
+#[cfg(feature = "async")]
+use crate::{error::Error, repos};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Class {
+    /// Daily only.
+    Flash,
+    /// Daily and weekly, not monthly.
+    Rising,
+    /// Daily, weekly and monthly.
+    Sustained,
+}
+
+/// 1-based rank on each period's page, `None` when not on it.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Ranks {
+    pub daily: Option<usize>,
+    pub weekly: Option<usize>,
+    pub monthly: Option<usize>,
+}
+
+impl Ranks {
+    pub fn class(&self) -> Option<Class> {
+        match (self.daily, self.weekly, self.monthly) {
+            (Some(_), None, None) => Some(Class::Flash),
+            (Some(_), Some(_), None) => Some(Class::Rising),
+            (Some(_), Some(_), Some(_)) => Some(Class::Sustained),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, since: Since) -> Option<usize> {
+        match since {
+            Since::Daily => self.daily,
+            Since::Weekly => self.weekly,
+            Since::Monthly => self.monthly,
+        }
+    }
+
+    fn get_mut(&mut self, since: Since) -> &mut Option<usize> {
+        match since {
+            Since::Daily => &mut self.daily,
+            Since::Weekly => &mut self.weekly,
+            Since::Monthly => &mut self.monthly,
+        }
+    }
+}
+
+/// `class` is `None` for the combinations without a name, e.g. weekly only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Classified {
+    #[serde(flatten)]
+    pub repository: Repository,
+    pub ranks: Ranks,
+    pub class: Option<Class>,
+}
+
+/// Join the three results by url. Every repository on any of them is
+/// returned, ordered by daily, then weekly, then monthly rank; the row is
+/// the one of the shortest period it is on.
+pub fn classify(
+    daily: &[Repository],
+    weekly: &[Repository],
+    monthly: &[Repository],
+) -> Vec<Classified> {
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut classified: Vec<Classified> = Vec::new();
+
+    for (since, data) in Since::all().zip([daily, weekly, monthly].iter()) {
+        for (i, x) in data.iter().enumerate() {
+            let url = match x.url.as_deref() {
+                Some(url) => url,
+                None => continue,
+            };
+            let at = *index.entry(url).or_insert_with(|| {
+                classified.push(Classified {
+                    repository: x.clone(),
+                    ranks: Ranks::default(),
+                    class: None,
+                });
+                classified.len() - 1
+            });
+            classified[at].ranks.get_mut(since).get_or_insert(i + 1);
+        }
+    }
+
+    for x in classified.iter_mut() {
+        x.class = x.ranks.class();
+    }
+
+    classified
+}
+
+/// Fetch `builder` for every `Since` at once and `classify` the results;
+/// the `since` of `builder` is ignored.
+#[cfg(feature = "async")]
+pub async fn compare(builder: &repos::Builder) -> Result<Vec<Classified>, Error> {
+    let fetch = |since: Since| builder.clone().since(since);
+    let (daily, weekly, monthly) = (
+        fetch(Since::Daily),
+        fetch(Since::Weekly),
+        fetch(Since::Monthly),
+    );
+
+    let (daily, weekly, monthly) =
+        futures_util::future::try_join3(daily.get_data(), weekly.get_data(), monthly.get_data())
+            .await?;
+
+    Ok(classify(&daily, &weekly, &monthly))
+}
@@ -0,0 +1,131 @@
+use crate::error::Error;
+use crate::{Since, GITHUB_TRENDING_URL};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::form_urlencoded;
+use url::Url;
+
+// github.com/trending/c%2B%2B, unreserved characters stay as they are
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    #[default]
+    Repositories,
+    Developers,
+}
+
+/// Everything that selects a github.com/trending page.
+///
+/// `language` is the decoded url param, e.g. `c++` rather than `c%2B%2B`;
+/// `to_url` takes care of the encoding.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrendingQuery {
+    pub kind: Kind,
+    pub language: Option<String>,
+    pub spoken_language: Option<String>,
+    pub since: Option<Since>,
+    pub sponsorable: bool,
+}
+
+impl TrendingQuery {
+    pub fn repositories() -> Self {
+        TrendingQuery::default()
+    }
+
+    pub fn developers() -> Self {
+        TrendingQuery {
+            kind: Kind::Developers,
+            ..TrendingQuery::default()
+        }
+    }
+
+    pub fn to_url(&self) -> String {
+        let mut url = String::from(GITHUB_TRENDING_URL);
+        if self.kind == Kind::Developers {
+            url.push_str("/developers");
+        }
+        if let Some(lang) = self.language.as_ref() {
+            url.push('/');
+            url.extend(utf8_percent_encode(lang, SEGMENT));
+        }
+
+        let mut params = form_urlencoded::Serializer::new(String::new());
+        if let Some(since) = self.since {
+            params.append_pair("since", since.to_str());
+        }
+        if let Some(s_lang) = self.spoken_language.as_ref() {
+            params.append_pair("spoken_language_code", s_lang);
+        }
+        if self.sponsorable {
+            params.append_pair("sponsorable", "1");
+        }
+
+        let params = params.finish();
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params);
+        }
+
+        url
+    }
+
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidUrl(url.to_string());
+        let parsed = Url::parse(url).map_err(|_| invalid())?;
+
+        let is_github = matches!(
+            parsed.host_str(),
+            Some("github.com") | Some("www.github.com")
+        );
+        if !matches!(parsed.scheme(), "http" | "https") || !is_github {
+            return Err(invalid());
+        }
+
+        let mut segments = parsed
+            .path_segments()
+            .ok_or_else(invalid)?
+            .filter(|x| !x.is_empty());
+
+        if segments.next() != Some("trending") {
+            return Err(invalid());
+        }
+
+        let mut query = TrendingQuery::repositories();
+        let mut lang = segments.next();
+        if lang == Some("developers") {
+            query.kind = Kind::Developers;
+            lang = segments.next();
+        }
+        if segments.next().is_some() {
+            return Err(invalid());
+        }
+        query.language = lang.map(decode);
+
+        // an empty value, e.g. a cleared filter, is the same as no value
+        for (key, val) in parsed.query_pairs().filter(|(_, val)| !val.is_empty()) {
+            match key.as_ref() {
+                "since" => {
+                    query.since = Some(val.parse().map_err(|_| invalid())?);
+                }
+                "spoken_language_code" => query.spoken_language = Some(val.into_owned()),
+                "sponsorable" => query.sponsorable = val == "1" || val == "true",
+                _ => {}
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+impl fmt::Display for TrendingQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_url())
+    }
+}
+
+pub(crate) fn decode(s: &str) -> String {
+    percent_decode_str(s).decode_utf8_lossy().into_owned()
+}
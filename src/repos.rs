@@ -1,10 +1,20 @@
+#[cfg(any(feature = "async", feature = "blocking"))]
 use crate::error::Error;
+#[cfg(feature = "parse")]
 use crate::*;
+#[cfg(feature = "parse")]
 use select::document::Document;
+#[cfg(feature = "parse")]
 use select::predicate::{Attr, Class, Name};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+#[cfg(feature = "async")]
+use serde_json::Value;
+#[cfg(any(feature = "async", feature = "blocking"))]
 use std::borrow::Borrow;
+#[cfg(any(feature = "async", feature = "blocking"))]
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuiltBy {
@@ -26,6 +36,10 @@ pub struct Repository {
     pub forks: Option<u32>,
     pub lang_color: Option<String>,
     pub built_by: Vec<BuiltBy>,
+    pub full_name: Option<String>,
+    #[serde(default)]
+    pub sponsorable: bool,
+    pub since: Option<String>,
 }
 
 impl Repository {
@@ -34,33 +48,55 @@ impl Repository {
     }
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
 #[derive(Debug, Clone)]
 pub struct Builder {
-    pro_lang: Option<String>,
-    spoken_lang: Option<String>,
-    since: Option<String>,
+    query: TrendingQuery,
+    pub(crate) fetch: FetchOptions,
+    pub(crate) schema: Schema,
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
 impl Builder {
+    /// Configure a builder from a pasted link such as
+    /// `https://github.com/trending/c%2B%2B?since=weekly`.
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let trending = TrendingQuery::from_url(url)?;
+        if trending.kind != query::Kind::Repositories {
+            return Err(Error::InvalidUrl(url.to_string()));
+        }
+
+        let mut builder = builder();
+        if let Some(lang) = trending.language.as_ref() {
+            builder = builder.programming_language(lang.as_str());
+        }
+        if let Some(s_lang) = trending.spoken_language.as_ref() {
+            builder = builder.spoken_language(s_lang.as_str());
+        }
+        if let Some(since) = trending.since {
+            builder = builder.since(since);
+        }
+        Ok(builder)
+    }
+
     pub fn programming_language<T: Borrow<str>>(mut self, lang: T) -> Self {
         let lang = lang.borrow();
         let lang_: Option<Language> = languages::find(By::Both(lang));
 
         match lang_ {
             Some(val) => {
-                self.pro_lang = Some(val.url_param);
+                self.query.language = Some(query::decode(&val.url_param));
                 self
             }
             _ => {
-                self.pro_lang = Some(lang.to_string());
+                self.query.language = Some(lang.to_string());
                 self
             }
         }
     }
 
     pub fn since(mut self, since: Since) -> Self {
-        let s: String = since.to_string();
-        self.since = Some(s);
+        self.query.since = Some(since);
         self
     }
 
@@ -70,43 +106,70 @@ impl Builder {
 
         match s_lang_ {
             Some(val) => {
-                self.spoken_lang = Some(val.url_param.clone());
+                self.query.spoken_language = Some(val.url_param);
                 self
             }
             _ => {
-                self.spoken_lang = Some(s_lang.to_string());
+                self.query.spoken_language = Some(s_lang.to_string());
                 self
             }
         }
     }
 
+    /// Shape of the records returned by `get_data_json`.
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Limit for a single HTTP request to GitHub.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.fetch.timeout(timeout);
+        self
+    }
+
+    /// Point in time after which fetching fails with `Error::Timeout`.
+    /// Share one deadline between builders to bound a whole fan-out.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.fetch.deadline(deadline);
+        self
+    }
+
+    /// Abort the fetch with `Error::Cancelled` once `token` is cancelled.
+    #[cfg(feature = "async")]
+    pub fn cancel_token(mut self, token: CancellationToken) -> Self {
+        self.fetch.cancel_token(token);
+        self
+    }
+
+    #[cfg(feature = "async")]
     pub async fn get_data_json(&self) -> Result<Value, Error> {
+        let schema = self.schema;
         let data = self.get_data().await?;
-        let data_json: Vec<Value> = data.into_iter().map(|x| json!(x)).collect();
 
-        Ok(Value::Array(data_json))
+        Ok(schema.repos_json(&data))
     }
 
+    #[cfg(feature = "async")]
     pub async fn get_data(&self) -> Result<Vec<Repository>, Error> {
-        let pro_lang_url: String = self
-            .pro_lang
-            .as_ref()
-            .map(|x| format!("/{}", x))
-            .unwrap_or("".to_string());
-
-        let optional_params: String = match (self.since.as_ref(), self.spoken_lang.as_ref()) {
-            (Some(s), Some(sl)) => format!("?since={}&spoken_language_code={}", s, sl),
-            (Some(s), None) => format!("?since={}", s),
-            (None, Some(sl)) => format!("?spoken_language_code={}", sl),
-            _ => "".to_string(),
-        };
-
-        let url = format!("{}{}{}", GITHUB_TRENDING_URL, pro_lang_url, optional_params);
-        fetch_html(&url).await.and_then(|x| Ok(select_data(&x)))
+        fetch_html(&self.url(), &self.fetch)
+            .await
+            .map(|x| parse(&x))
+    }
+
+    pub fn query(&self) -> &TrendingQuery {
+        &self.query
+    }
+
+    pub(crate) fn url(&self) -> String {
+        self.query.to_url()
     }
 }
 
-fn select_data(html: &str) -> Vec<Repository> {
+/// Parse the rows of an already downloaded trending page.
+#[cfg(feature = "parse")]
+#[allow(clippy::useless_conversion, clippy::bind_instead_of_map)]
+pub fn parse(html: &str) -> Vec<Repository> {
     let document = Document::from(html);
 
     let data: Vec<Repository> = document
@@ -143,6 +206,17 @@ fn select_data(html: &str) -> Vec<Repository> {
                 })
                 .unwrap_or((None, None));
 
+            let full_name: Option<String> = node
+                .find(Name("h2"))
+                .next()
+                .and_then(|x| x.find(Name("a")).next())
+                .map(|x| escape(x.text()).replace(" / ", "/"));
+
+            let sponsorable: bool = node
+                .find(Name("a"))
+                .filter_map(|x| x.attr("href"))
+                .any(|x| x.starts_with("/sponsors/"));
+
             let current_star: Option<u32> =
                 node.find(Class("float-sm-right")).next().and_then(|tag| {
                     let x = tag.text();
@@ -150,15 +224,20 @@ fn select_data(html: &str) -> Vec<Repository> {
                     Some(s[0].replace(",", "").parse::<u32>().unwrap())
                 });
 
+            // "1,024 stars today" -> "today", "this week", "this month"
+            let since: Option<String> = node
+                .find(Class("float-sm-right"))
+                .next()
+                .map(|tag| escape(tag.text()))
+                .and_then(|x| x.split_once("stars").map(|(_, s)| s.trim().to_string()))
+                .filter(|x| !x.is_empty());
+
             let lang: Option<String> = node
                 .find(Attr("itemprop", "programmingLanguage"))
                 .next()
-                .and_then(|x| Some(x.text()));
+                .map(|x| x.text());
 
-            let desc: Option<String> = node
-                .find(Name("p"))
-                .next()
-                .and_then(|x| Some(escape(x.text())));
+            let desc: Option<String> = node.find(Name("p")).next().map(|x| escape(x.text()));
 
             let url: Option<String> = match (username.clone(), reponame.clone()) {
                 (Some(username), Some(reponame)) => {
@@ -196,7 +275,7 @@ fn select_data(html: &str) -> Vec<Repository> {
                     let avatar = x.attr("src").and_then(|a| {
                         let ss: Vec<&str> = a.split("?").collect();
 
-                        if ss.len() > 0 {
+                        if !ss.is_empty() {
                             Some(ss[0].to_string())
                         } else {
                             None
@@ -206,18 +285,16 @@ fn select_data(html: &str) -> Vec<Repository> {
                         .clone()
                         .map(|x| format!("{}/{}", GITHUB_BASE_URL, x));
 
-                    let built_by = BuiltBy {
+                    BuiltBy {
                         username,
                         avatar,
                         href,
-                    };
-
-                    built_by
+                    }
                 })
                 .collect::<Vec<_>>();
 
             // println!("x: {:?}", stars_forks);
-            return Repository {
+            Repository {
                 avatar: username
                     .clone()
                     .map(|x| format!("{}/{}.png", GITHUB_BASE_URL, x)),
@@ -228,26 +305,30 @@ fn select_data(html: &str) -> Vec<Repository> {
                 description: desc,
                 url,
                 stars: match stars_forks.len() {
-                    n if n > 0 => Some(stars_forks[0].clone()),
+                    n if n > 0 => Some(stars_forks[0]),
                     _ => None,
                 },
                 forks: match stars_forks.len() {
-                    n if n > 1 => Some(stars_forks[1].clone()),
+                    n if n > 1 => Some(stars_forks[1]),
                     _ => None,
                 },
                 built_by,
                 lang_color,
-            };
+                full_name,
+                sponsorable,
+                since,
+            }
         })
         .collect();
 
     data
 }
 
+#[cfg(any(feature = "async", feature = "blocking"))]
 pub fn builder() -> Builder {
     Builder {
-        pro_lang: None,
-        spoken_lang: None,
-        since: None,
+        query: TrendingQuery::repositories(),
+        fetch: FetchOptions::default(),
+        schema: Schema::default(),
     }
 }
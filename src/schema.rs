@@ -0,0 +1,136 @@
+//! Versioned JSON shapes of `Repository` and `Developer`.
+//!
+//! `V1Snake` is what the structs serialize to. `GithubTrendingApiCompat`
+//! mirrors the field names of the github-trending-api
+//! (`currentPeriodStars`, `languageColor`, `builtBy`, ...). The JSON Schema
+//! documents of both live in `schemas/` and are returned by
+//! `repository_json_schema` / `developer_json_schema`.
+
+use crate::developers::Developer;
+use crate::error::Error;
+use crate::repos::Repository;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Schema {
+    #[default]
+    V1Snake,
+    GithubTrendingApiCompat,
+}
+
+impl Schema {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::V1Snake => "v1-snake",
+            Self::GithubTrendingApiCompat => "github-trending-api-compat",
+        }
+    }
+
+    pub fn all() -> impl Iterator<Item = Schema> {
+        [Self::V1Snake, Self::GithubTrendingApiCompat]
+            .iter()
+            .copied()
+    }
+
+    pub fn repository(&self, x: &Repository) -> Value {
+        match self {
+            Self::V1Snake => json!(x),
+            Self::GithubTrendingApiCompat => {
+                let built_by: Vec<Value> = x
+                    .built_by
+                    .iter()
+                    .map(|b| json!({ "username": b.username, "href": b.href, "avatar": b.avatar }))
+                    .collect();
+
+                json!({
+                    "author": x.author,
+                    "name": x.name,
+                    "avatar": x.avatar,
+                    "url": x.url,
+                    "description": x.description,
+                    "language": x.programming_language,
+                    "languageColor": x.lang_color,
+                    "stars": x.stars,
+                    "forks": x.forks,
+                    "currentPeriodStars": x.current_star,
+                    "builtBy": built_by,
+                })
+            }
+        }
+    }
+
+    pub fn developer(&self, x: &Developer) -> Value {
+        match self {
+            Self::V1Snake => json!(x),
+            Self::GithubTrendingApiCompat => {
+                let repo = x
+                    .repo
+                    .as_ref()
+                    .map(|r| json!({ "name": r.name, "description": r.description, "url": r.url }));
+
+                json!({
+                    "username": x.username,
+                    "name": x.name,
+                    "type": "user",
+                    "url": x.url,
+                    "sponsorUrl": x.sponsor_url,
+                    "avatar": x.avatar,
+                    "repo": repo,
+                })
+            }
+        }
+    }
+
+    pub fn repos_json(&self, data: &[Repository]) -> Value {
+        Value::Array(data.iter().map(|x| self.repository(x)).collect())
+    }
+
+    pub fn developers_json(&self, data: &[Developer]) -> Value {
+        Value::Array(data.iter().map(|x| self.developer(x)).collect())
+    }
+
+    /// JSON Schema (draft 2020-12) of a single repository.
+    pub fn repository_json_schema(&self) -> &'static str {
+        match self {
+            Self::V1Snake => include_str!("../schemas/v1-snake/repository.json"),
+            Self::GithubTrendingApiCompat => {
+                include_str!("../schemas/github-trending-api-compat/repository.json")
+            }
+        }
+    }
+
+    /// JSON Schema (draft 2020-12) of a single developer.
+    pub fn developer_json_schema(&self) -> &'static str {
+        match self {
+            Self::V1Snake => include_str!("../schemas/v1-snake/developer.json"),
+            Self::GithubTrendingApiCompat => {
+                include_str!("../schemas/github-trending-api-compat/developer.json")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl FromStr for Schema {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Schema::all()
+            .find(|x| x.to_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                Error::Others(format!(
+                    "expected v1-snake or github-trending-api-compat, got `{}`",
+                    s
+                ))
+            })
+    }
+}
@@ -0,0 +1,226 @@
+//! JSON HTTP API over the builders, enabled by the `server` feature.
+//!
+//! Routes:
+//! - `GET /repositories?language=&since=&spoken_language_code=&schema=`
+//! - `GET /developers?language=&since=&sponsorable=&schema=`
+//! - `GET /languages`
+//! - `GET /spoken_languages`
+//!
+//! `/repositories` and `/developers` are also served as feeds with a `.rss`
+//! or `.atom` suffix, taking the same params. `schema` is one of `v1-snake`
+//! (the default) or `github-trending-api-compat`.
+
+use crate::error::Error;
+use crate::export::feed::Feed;
+use crate::{developers, languages, repos, spoken_languages, Schema, Since};
+use axum::extract::Query;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+// per GitHub request, so a hung connection does not hold the response forever
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct Params {
+    language: Option<String>,
+    since: Option<String>,
+    spoken_language_code: Option<String>,
+    sponsorable: Option<String>,
+    schema: Option<String>,
+}
+
+impl Params {
+    fn since(&self) -> Result<Option<Since>, &'static str> {
+        match self.since.as_ref() {
+            Some(s) => s.parse().map(Some).map_err(|_| "invalid since"),
+            None => Ok(None),
+        }
+    }
+
+    // `1` like github.com, or `true`; empty is the same as absent
+    fn sponsorable(&self) -> Result<bool, &'static str> {
+        match self
+            .sponsorable
+            .as_deref()
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            None | Some("") | Some("0") | Some("false") => Ok(false),
+            Some("1") | Some("true") => Ok(true),
+            Some(_) => Err("invalid sponsorable"),
+        }
+    }
+
+    fn schema(&self) -> Result<Schema, &'static str> {
+        match self.schema.as_ref() {
+            Some(s) => s.parse().map_err(|_| "invalid schema"),
+            None => Ok(Schema::default()),
+        }
+    }
+
+    fn repos(&self) -> Result<repos::Builder, &'static str> {
+        let since = self.since()?;
+
+        let mut builder = repos::builder().timeout(TIMEOUT).schema(self.schema()?);
+        if let Some(lang) = self.language.as_ref() {
+            builder = builder.programming_language(lang.as_str());
+        }
+        if let Some(s_lang) = self.spoken_language_code.as_ref() {
+            builder = builder.spoken_language(s_lang.as_str());
+        }
+        if let Some(since) = since {
+            builder = builder.since(since);
+        }
+
+        Ok(builder)
+    }
+
+    fn developers(&self) -> Result<developers::Builder, &'static str> {
+        let since = self.since()?;
+
+        let mut builder = developers::builder()
+            .timeout(TIMEOUT)
+            .sponsorable(self.sponsorable()?)
+            .schema(self.schema()?);
+        if let Some(lang) = self.language.as_ref() {
+            builder = builder.programming_language(lang.as_str());
+        }
+        if let Some(since) = since {
+            builder = builder.since(since);
+        }
+
+        Ok(builder)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FeedFormat {
+    Rss,
+    Atom,
+}
+
+impl FeedFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+        }
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": msg }))).into_response()
+}
+
+fn fetch_error_response(e: Error) -> Response {
+    let status = match e {
+        Error::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::BAD_GATEWAY,
+    };
+    error_response(status, &e.to_string())
+}
+
+async fn repositories(Query(params): Query<Params>) -> Response {
+    let builder = match params.repos() {
+        Ok(builder) => builder,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+
+    match builder.get_data_json().await {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => fetch_error_response(e),
+    }
+}
+
+async fn developers(Query(params): Query<Params>) -> Response {
+    let builder = match params.developers() {
+        Ok(builder) => builder,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+
+    match builder.get_data_json().await {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => fetch_error_response(e),
+    }
+}
+
+async fn repositories_feed(format: FeedFormat, params: Params) -> Response {
+    let builder = match params.repos() {
+        Ok(builder) => builder,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+    let feed = Feed::new(builder.query());
+
+    match builder.get_data().await {
+        Ok(data) => {
+            let body = match format {
+                FeedFormat::Rss => feed.repos_rss(&data),
+                FeedFormat::Atom => feed.repos_atom(&data),
+            };
+            ([(header::CONTENT_TYPE, format.content_type())], body).into_response()
+        }
+        Err(e) => fetch_error_response(e),
+    }
+}
+
+async fn developers_feed(format: FeedFormat, params: Params) -> Response {
+    let builder = match params.developers() {
+        Ok(builder) => builder,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+    let feed = Feed::new(builder.query());
+
+    match builder.get_data().await {
+        Ok(data) => {
+            let body = match format {
+                FeedFormat::Rss => feed.developers_rss(&data),
+                FeedFormat::Atom => feed.developers_atom(&data),
+            };
+            ([(header::CONTENT_TYPE, format.content_type())], body).into_response()
+        }
+        Err(e) => fetch_error_response(e),
+    }
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/repositories", get(repositories))
+        .route(
+            "/repositories.rss",
+            get(|Query(params)| repositories_feed(FeedFormat::Rss, params)),
+        )
+        .route(
+            "/repositories.atom",
+            get(|Query(params)| repositories_feed(FeedFormat::Atom, params)),
+        )
+        .route("/developers", get(developers))
+        .route(
+            "/developers.rss",
+            get(|Query(params)| developers_feed(FeedFormat::Rss, params)),
+        )
+        .route(
+            "/developers.atom",
+            get(|Query(params)| developers_feed(FeedFormat::Atom, params)),
+        )
+        .route(
+            "/languages",
+            get(|| async { Json(languages::get_data_json()) }),
+        )
+        .route(
+            "/spoken_languages",
+            get(|| async { Json(spoken_languages::get_data_json()) }),
+        )
+}
+
+pub async fn serve(addr: SocketAddr) -> Result<(), Error> {
+    axum::Server::try_bind(&addr)
+        .map_err(|e| Error::Others(e.to_string()))?
+        .serve(router().into_make_service())
+        .await
+        .map_err(|e| Error::Others(e.to_string()))
+}
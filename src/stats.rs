@@ -0,0 +1,93 @@
+//! Summary numbers of a trending result, e.g. of a month of snapshots.
+//!
+//! ```no_run
+//! use gtrend::stats;
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let data = gtrend::repos::builder().since(gtrend::Since::Monthly).get_data().await?;
+//! let summary = stats::summarize(&data);
+//!
+//! for x in summary.languages.iter().take(5) {
+//!     println!("{} {}", x.name, x.count);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::repos::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Every list is sorted by count, most frequent first, then by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub repositories: usize,
+    /// By `programming_language`.
+    pub languages: Vec<Count>,
+    pub without_language: usize,
+    pub total_current_stars: u64,
+    /// Of the repositories with a `current_star`.
+    pub median_current_stars: Option<f64>,
+    /// Users and organizations owning the repositories.
+    pub authors: Vec<Count>,
+    /// `built_by` usernames.
+    pub contributors: Vec<Count>,
+}
+
+fn counts<'a>(names: impl Iterator<Item = &'a str>) -> Vec<Count> {
+    let mut map: HashMap<&str, usize> = HashMap::new();
+    for name in names {
+        *map.entry(name).or_default() += 1;
+    }
+
+    let mut counts: Vec<Count> = map
+        .into_iter()
+        .map(|(name, count)| Count {
+            name: name.to_string(),
+            count,
+        })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+    counts
+}
+
+fn median(mut values: Vec<u32>) -> Option<f64> {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+
+    match values.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((f64::from(values[mid - 1]) + f64::from(values[mid])) / 2.0),
+        _ => Some(f64::from(values[mid])),
+    }
+}
+
+pub fn summarize(data: &[Repository]) -> Stats {
+    let current_stars: Vec<u32> = data.iter().filter_map(|x| x.current_star).collect();
+
+    Stats {
+        repositories: data.len(),
+        languages: counts(
+            data.iter()
+                .filter_map(|x| x.programming_language.as_deref()),
+        ),
+        without_language: data
+            .iter()
+            .filter(|x| x.programming_language.is_none())
+            .count(),
+        total_current_stars: current_stars.iter().map(|&x| u64::from(x)).sum(),
+        median_current_stars: median(current_stars),
+        authors: counts(data.iter().filter_map(|x| x.author.as_deref())),
+        contributors: counts(
+            data.iter()
+                .flat_map(|x| x.built_by.iter())
+                .filter_map(|x| x.username.as_deref()),
+        ),
+    }
+}
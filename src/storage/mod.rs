@@ -0,0 +1,4 @@
+//! Long-term storage of fetched results.
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
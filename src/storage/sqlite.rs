@@ -0,0 +1,502 @@
+//! SQLite storage of every fetch, enabled by the `sqlite` feature.
+//!
+//! A fetch is a row of `fetches` (the query and `fetched_at` in unix
+//! seconds) with its rows in `repositories` and `built_by`, or in
+//! `developers`, ranked from 1. The schema is migrated when a store is
+//! opened, so the tables can also be queried with plain SQL.
+//!
+//! ```no_run
+//! use gtrend::storage::sqlite::Store;
+//! use std::time::{Duration, SystemTime};
+//! # async fn run() -> Result<(), gtrend::Error> {
+//! let mut store = Store::open("trending.db")?;
+//!
+//! let builder = gtrend::repos::builder().programming_language("rust");
+//! store.insert_repos(builder.query(), SystemTime::now(), &builder.get_data().await?)?;
+//!
+//! let month_ago = SystemTime::now() - Duration::from_secs(30 * 86_400);
+//! for x in store.top_languages(month_ago)?.iter().take(5) {
+//!     println!("{} {}", x.name, x.count);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::developers::{Developer, Repo};
+use crate::error::Error;
+use crate::momentum::Snapshot;
+use crate::query::{Kind, TrendingQuery};
+use crate::repos::{BuiltBy, Repository};
+use crate::stats::Count;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// applied in order, `PRAGMA user_version` is the number applied so far
+const MIGRATIONS: &[&str] = &[r#"
+CREATE TABLE fetches (
+    id INTEGER PRIMARY KEY,
+    fetched_at INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    language TEXT,
+    spoken_language TEXT,
+    since TEXT,
+    sponsorable INTEGER NOT NULL
+);
+CREATE INDEX fetches_fetched_at ON fetches (fetched_at);
+
+CREATE TABLE repositories (
+    fetch_id INTEGER NOT NULL REFERENCES fetches (id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    author TEXT,
+    name TEXT,
+    full_name TEXT,
+    url TEXT,
+    avatar TEXT,
+    description TEXT,
+    programming_language TEXT,
+    lang_color TEXT,
+    stars INTEGER,
+    forks INTEGER,
+    current_star INTEGER,
+    since TEXT,
+    sponsorable INTEGER NOT NULL,
+    PRIMARY KEY (fetch_id, rank)
+);
+CREATE INDEX repositories_url ON repositories (url);
+CREATE INDEX repositories_full_name ON repositories (full_name);
+
+CREATE TABLE built_by (
+    fetch_id INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    username TEXT,
+    href TEXT,
+    avatar TEXT,
+    PRIMARY KEY (fetch_id, rank, position),
+    FOREIGN KEY (fetch_id, rank) REFERENCES repositories (fetch_id, rank) ON DELETE CASCADE
+);
+
+CREATE TABLE developers (
+    fetch_id INTEGER NOT NULL REFERENCES fetches (id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    name TEXT,
+    username TEXT,
+    url TEXT,
+    avatar TEXT,
+    organization TEXT,
+    sponsorable INTEGER NOT NULL,
+    sponsor_url TEXT,
+    repo_name TEXT,
+    repo_description TEXT,
+    repo_url TEXT,
+    repo_label TEXT,
+    PRIMARY KEY (fetch_id, rank)
+);
+CREATE INDEX developers_username ON developers (username);
+"#];
+
+const REPOSITORY_COLUMNS: &str = "author, name, full_name, url, avatar, description, \
+    programming_language, lang_color, stars, forks, current_star, since, sponsorable";
+
+const DEVELOPER_COLUMNS: &str = "name, username, url, avatar, organization, sponsorable, \
+    sponsor_url, repo_name, repo_description, repo_url, repo_label";
+
+const FETCH_COLUMNS: &str = "f.id, f.fetched_at, f.kind, f.language, f.spoken_language, \
+    f.since, f.sponsorable";
+
+/// One stored fetch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fetch {
+    pub id: i64,
+    pub fetched_at: SystemTime,
+    pub query: TrendingQuery,
+}
+
+/// A repository on the page of one fetch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Appearance {
+    pub fetch: Fetch,
+    pub rank: usize,
+    pub stars: Option<u32>,
+    pub forks: Option<u32>,
+    pub current_star: Option<u32>,
+}
+
+fn to_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn from_secs(secs: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64)
+}
+
+fn kind_to_str(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Repositories => "repositories",
+        Kind::Developers => "developers",
+    }
+}
+
+// the columns of FETCH_COLUMNS from `offset`
+fn fetch(row: &Row, offset: usize) -> rusqlite::Result<Fetch> {
+    let kind = match row.get::<_, String>(offset + 2)?.as_str() {
+        "developers" => Kind::Developers,
+        _ => Kind::Repositories,
+    };
+    let since: Option<String> = row.get(offset + 5)?;
+
+    Ok(Fetch {
+        id: row.get(offset)?,
+        fetched_at: from_secs(row.get(offset + 1)?),
+        query: TrendingQuery {
+            kind,
+            language: row.get(offset + 3)?,
+            spoken_language: row.get(offset + 4)?,
+            since: since.and_then(|x| x.parse().ok()),
+            sponsorable: row.get(offset + 6)?,
+        },
+    })
+}
+
+fn repository(row: &Row) -> rusqlite::Result<Repository> {
+    Ok(Repository {
+        author: row.get(0)?,
+        name: row.get(1)?,
+        full_name: row.get(2)?,
+        url: row.get(3)?,
+        avatar: row.get(4)?,
+        description: row.get(5)?,
+        programming_language: row.get(6)?,
+        lang_color: row.get(7)?,
+        stars: row.get(8)?,
+        forks: row.get(9)?,
+        current_star: row.get(10)?,
+        since: row.get(11)?,
+        sponsorable: row.get(12)?,
+        built_by: Vec::new(),
+    })
+}
+
+fn developer(row: &Row) -> rusqlite::Result<Developer> {
+    let repo = Repo {
+        name: row.get(7)?,
+        description: row.get(8)?,
+        url: row.get(9)?,
+        label: row.get(10)?,
+    };
+    let has_repo = repo.name.is_some()
+        || repo.description.is_some()
+        || repo.url.is_some()
+        || repo.label.is_some();
+
+    Ok(Developer {
+        name: row.get(0)?,
+        username: row.get(1)?,
+        url: row.get(2)?,
+        avatar: row.get(3)?,
+        organization: row.get(4)?,
+        sponsorable: row.get(5)?,
+        sponsor_url: row.get(6)?,
+        repo: if has_repo { Some(repo) } else { None },
+    })
+}
+
+/// Migrate `conn` to the latest schema.
+pub fn migrate(conn: &mut Connection) -> Result<(), Error> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(Error::Others(format!(
+            "database schema version {} is newer than the supported {}",
+            version,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// A SQLite database of fetches.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Store::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Store::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Turns on foreign keys and migrates `conn`.
+    pub fn from_connection(mut conn: Connection) -> Result<Self, Error> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Store { conn })
+    }
+
+    /// For queries the helpers below do not cover.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    fn insert_fetch(
+        tx: &Transaction,
+        query: &TrendingQuery,
+        fetched_at: SystemTime,
+    ) -> Result<i64, Error> {
+        tx.execute(
+            "INSERT INTO fetches (fetched_at, kind, language, spoken_language, since, sponsorable)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                to_secs(fetched_at),
+                kind_to_str(query.kind),
+                query.language,
+                query.spoken_language,
+                query.since.map(|x| x.to_str()),
+                query.sponsorable,
+            ],
+        )?;
+        Ok(tx.last_insert_rowid())
+    }
+
+    /// Store the result of `query`, returns the id of the fetch.
+    pub fn insert_repos(
+        &mut self,
+        query: &TrendingQuery,
+        fetched_at: SystemTime,
+        data: &[Repository],
+    ) -> Result<i64, Error> {
+        let tx = self.conn.transaction()?;
+        let id = Store::insert_fetch(&tx, query, fetched_at)?;
+        {
+            let mut repo = tx.prepare(&format!(
+                "INSERT INTO repositories (fetch_id, rank, {})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                REPOSITORY_COLUMNS
+            ))?;
+            let mut built_by = tx.prepare(
+                "INSERT INTO built_by (fetch_id, rank, position, username, href, avatar)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+
+            for (i, x) in data.iter().enumerate() {
+                repo.execute(params![
+                    id,
+                    i + 1,
+                    x.author,
+                    x.name,
+                    x.full_name,
+                    x.url,
+                    x.avatar,
+                    x.description,
+                    x.programming_language,
+                    x.lang_color,
+                    x.stars,
+                    x.forks,
+                    x.current_star,
+                    x.since,
+                    x.sponsorable,
+                ])?;
+                for (j, b) in x.built_by.iter().enumerate() {
+                    built_by.execute(params![id, i + 1, j, b.username, b.href, b.avatar])?;
+                }
+            }
+        }
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    pub fn insert_developers(
+        &mut self,
+        query: &TrendingQuery,
+        fetched_at: SystemTime,
+        data: &[Developer],
+    ) -> Result<i64, Error> {
+        let tx = self.conn.transaction()?;
+        let id = Store::insert_fetch(&tx, query, fetched_at)?;
+        {
+            let mut developer = tx.prepare(&format!(
+                "INSERT INTO developers (fetch_id, rank, {})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                DEVELOPER_COLUMNS
+            ))?;
+
+            for (i, x) in data.iter().enumerate() {
+                let repo = x.repo.as_ref();
+                developer.execute(params![
+                    id,
+                    i + 1,
+                    x.name,
+                    x.username,
+                    x.url,
+                    x.avatar,
+                    x.organization,
+                    x.sponsorable,
+                    x.sponsor_url,
+                    repo.and_then(|r| r.name.as_ref()),
+                    repo.and_then(|r| r.description.as_ref()),
+                    repo.and_then(|r| r.url.as_ref()),
+                    repo.and_then(|r| r.label.as_ref()),
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    /// Every fetch, oldest first.
+    pub fn fetches(&self) -> Result<Vec<Fetch>, Error> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM fetches f ORDER BY f.fetched_at, f.id",
+            FETCH_COLUMNS
+        ))?;
+        let fetches = stmt
+            .query_map([], |row| fetch(row, 0))?
+            .collect::<Result<_, _>>()?;
+        Ok(fetches)
+    }
+
+    pub fn fetch(&self, id: i64) -> Result<Option<Fetch>, Error> {
+        let fetch = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM fetches f WHERE f.id = ?1", FETCH_COLUMNS),
+                [id],
+                |row| fetch(row, 0),
+            )
+            .optional()?;
+        Ok(fetch)
+    }
+
+    /// The repositories of a fetch in the order they were on the page.
+    pub fn repos(&self, fetch_id: i64) -> Result<Vec<Repository>, Error> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM repositories WHERE fetch_id = ?1 ORDER BY rank",
+            REPOSITORY_COLUMNS
+        ))?;
+        let mut data: Vec<Repository> = stmt
+            .query_map([fetch_id], repository)?
+            .collect::<Result<_, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT rank, username, href, avatar FROM built_by
+             WHERE fetch_id = ?1 ORDER BY rank, position",
+        )?;
+        let mut rows = stmt.query([fetch_id])?;
+        while let Some(row) = rows.next()? {
+            let rank: usize = row.get(0)?;
+            if let Some(x) = data.get_mut(rank - 1) {
+                x.built_by.push(BuiltBy {
+                    username: row.get(1)?,
+                    href: row.get(2)?,
+                    avatar: row.get(3)?,
+                });
+            }
+        }
+
+        Ok(data)
+    }
+
+    pub fn developers(&self, fetch_id: i64) -> Result<Vec<Developer>, Error> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM developers WHERE fetch_id = ?1 ORDER BY rank",
+            DEVELOPER_COLUMNS
+        ))?;
+        let data = stmt
+            .query_map([fetch_id], developer)?
+            .collect::<Result<_, _>>()?;
+        Ok(data)
+    }
+
+    /// Every fetch a repository was in, oldest first; `repo` is its url or
+    /// `full_name`, e.g. `tokio-rs/tokio`.
+    pub fn repo_appearances(&self, repo: &str) -> Result<Vec<Appearance>, Error> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, r.rank, r.stars, r.forks, r.current_star
+             FROM repositories r JOIN fetches f ON f.id = r.fetch_id
+             WHERE r.url = ?1 OR r.full_name = ?1
+             ORDER BY f.fetched_at, f.id",
+            FETCH_COLUMNS
+        ))?;
+        let appearances = stmt
+            .query_map([repo], |row| {
+                Ok(Appearance {
+                    fetch: fetch(row, 0)?,
+                    rank: row.get(7)?,
+                    stars: row.get(8)?,
+                    forks: row.get(9)?,
+                    current_star: row.get(10)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(appearances)
+    }
+
+    /// Distinct repositories per `programming_language` over the fetches
+    /// since `from`, most first.
+    pub fn top_languages(&self, from: SystemTime) -> Result<Vec<Count>, Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.programming_language, COUNT(DISTINCT r.url) AS count
+             FROM repositories r JOIN fetches f ON f.id = r.fetch_id
+             WHERE f.fetched_at >= ?1 AND r.programming_language IS NOT NULL
+             GROUP BY r.programming_language
+             ORDER BY count DESC, r.programming_language",
+        )?;
+        let counts = stmt
+            .query_map([to_secs(from)], |row| {
+                Ok(Count {
+                    name: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(counts)
+    }
+
+    /// The repository fetches of exactly `query` as snapshots, oldest
+    /// first, e.g. for `momentum::rank`.
+    pub fn snapshots(&self, query: &TrendingQuery) -> Result<Vec<Snapshot>, Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, fetched_at FROM fetches
+             WHERE kind = 'repositories' AND language IS ?1 AND spoken_language IS ?2
+                 AND since IS ?3 AND sponsorable = ?4
+             ORDER BY fetched_at, id",
+        )?;
+        let fetches: Vec<(i64, i64)> = stmt
+            .query_map(
+                params![
+                    query.language,
+                    query.spoken_language,
+                    query.since.map(|x| x.to_str()),
+                    query.sponsorable,
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?
+            .collect::<Result<_, _>>()?;
+
+        fetches
+            .into_iter()
+            .map(|(id, fetched_at)| {
+                Ok(Snapshot {
+                    taken_at: from_secs(fetched_at),
+                    data: self.repos(id)?,
+                })
+            })
+            .collect()
+    }
+}
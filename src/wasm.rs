@@ -0,0 +1,31 @@
+//! wasm-bindgen entry points for running the parser on pages that are
+//! already loaded, e.g. from a browser extension. Enabled by the `wasm`
+//! feature; the JSON has the same shape as `get_data_json`.
+
+use crate::{developers, languages, repos, spoken_languages};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+fn to_json<T: Serialize>(data: &T) -> Result<String, JsValue> {
+    serde_json::to_string(data).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen(js_name = parseRepos)]
+pub fn parse_repos(html: &str) -> Result<String, JsValue> {
+    to_json(&repos::parse(html))
+}
+
+#[wasm_bindgen(js_name = parseDevelopers)]
+pub fn parse_developers(html: &str) -> Result<String, JsValue> {
+    to_json(&developers::parse(html))
+}
+
+#[wasm_bindgen]
+pub fn languages() -> Result<String, JsValue> {
+    to_json(&languages::get_data())
+}
+
+#[wasm_bindgen(js_name = spokenLanguages)]
+pub fn spoken_languages() -> Result<String, JsValue> {
+    to_json(&spoken_languages::get_data())
+}
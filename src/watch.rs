@@ -0,0 +1,271 @@
+//! Poll a builder at an interval and turn the differences between two
+//! polls into events.
+//!
+//! ```no_run
+//! use futures_util::stream::StreamExt;
+//! use gtrend::watch::{self, Event};
+//! use std::time::Duration;
+//! # async fn run() {
+//! let builder = gtrend::repos::builder().programming_language("rust");
+//! let events = watch::repos(builder).interval(Duration::from_secs(600)).stream();
+//! futures_util::pin_mut!(events);
+//!
+//! while let Some(event) = events.next().await {
+//!     if let Ok(Event::Entered { rank, item }) = event {
+//!         println!("#{} {:?}", rank, item.full_name);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! The first poll only records a baseline unless `emit_initial` is set.
+//! Both the fetcher and the clock can be swapped out, e.g. to replay
+//! fixtures in tests.
+
+use crate::developers::{self, Developer};
+use crate::error::Error;
+use crate::repos::{self, Repository};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+pub type Fetched<T> = Pin<Box<dyn Future<Output = Result<Vec<T>, Error>> + Send>>;
+
+/// Rows are matched between polls by `key`.
+pub trait Watched: Clone {
+    fn key(&self) -> Option<&str>;
+    fn stars(&self) -> Option<u32>;
+}
+
+impl Watched for Repository {
+    fn key(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn stars(&self) -> Option<u32> {
+        self.stars
+    }
+}
+
+impl Watched for Developer {
+    fn key(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn stars(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Ranks start at 1, like on the trending page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<T> {
+    Entered {
+        rank: usize,
+        item: T,
+    },
+    Left {
+        rank: usize,
+        item: T,
+    },
+    RankChanged {
+        from: usize,
+        to: usize,
+        item: T,
+    },
+    StarsChanged {
+        from: Option<u32>,
+        to: Option<u32>,
+        item: T,
+    },
+}
+
+impl<T> Event<T> {
+    pub fn item(&self) -> &T {
+        match self {
+            Event::Entered { item, .. }
+            | Event::Left { item, .. }
+            | Event::RankChanged { item, .. }
+            | Event::StarsChanged { item, .. } => item,
+        }
+    }
+}
+
+pub trait Clock {
+    fn sleep(&mut self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn sleep(&mut self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+pub struct Watcher<F, C = TokioClock> {
+    fetch: F,
+    clock: C,
+    interval: Duration,
+    emit_initial: bool,
+}
+
+impl<F> Watcher<F> {
+    /// Watch whatever `fetch` returns, polled every 5 minutes by default.
+    pub fn new(fetch: F) -> Self {
+        Watcher {
+            fetch,
+            clock: TokioClock,
+            interval: Duration::from_secs(300),
+            emit_initial: false,
+        }
+    }
+}
+
+impl<F, C: Clock> Watcher<F, C> {
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Report every row of the first poll as `Entered`.
+    pub fn emit_initial(mut self, emit_initial: bool) -> Self {
+        self.emit_initial = emit_initial;
+        self
+    }
+
+    pub fn clock<C2: Clock>(self, clock: C2) -> Watcher<F, C2> {
+        Watcher {
+            fetch: self.fetch,
+            clock,
+            interval: self.interval,
+            emit_initial: self.emit_initial,
+        }
+    }
+
+    /// Never ends; a failed poll yields the error and is retried after the
+    /// next interval.
+    pub fn stream<T, Fut>(self) -> impl Stream<Item = Result<Event<T>, Error>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Vec<T>, Error>>,
+        T: Watched,
+    {
+        let state = State {
+            watcher: self,
+            previous: None,
+            pending: VecDeque::new(),
+            polled: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((Ok(event), state));
+                }
+
+                if state.polled {
+                    let interval = state.watcher.interval;
+                    state.watcher.clock.sleep(interval).await;
+                }
+                state.polled = true;
+
+                let current = match (state.watcher.fetch)().await {
+                    Ok(current) => current,
+                    Err(e) => return Some((Err(e), state)),
+                };
+                match state.previous.as_ref() {
+                    Some(previous) => state.pending.extend(diff(previous, &current)),
+                    None if state.watcher.emit_initial => state.pending.extend(diff(&[], &current)),
+                    None => {}
+                }
+                state.previous = Some(current);
+            }
+        })
+    }
+}
+
+struct State<F, C, T> {
+    watcher: Watcher<F, C>,
+    previous: Option<Vec<T>>,
+    pending: VecDeque<Event<T>>,
+    polled: bool,
+}
+
+/// Events that turn `previous` into `current`: entries, rank and star
+/// changes in the order of `current`, followed by the rows that left.
+pub fn diff<T: Watched>(previous: &[T], current: &[T]) -> Vec<Event<T>> {
+    let ranks = |data: &[T]| -> HashMap<String, usize> {
+        data.iter()
+            .enumerate()
+            .filter_map(|(i, x)| x.key().map(|k| (k.to_string(), i + 1)))
+            .collect()
+    };
+    let previous_ranks = ranks(previous);
+    let current_ranks = ranks(current);
+
+    let mut events = Vec::new();
+    for (i, x) in current.iter().enumerate() {
+        let key = match x.key() {
+            Some(key) => key,
+            None => continue,
+        };
+        let rank = i + 1;
+
+        match previous_ranks.get(key) {
+            None => events.push(Event::Entered {
+                rank,
+                item: x.clone(),
+            }),
+            Some(&from) => {
+                if from != rank {
+                    events.push(Event::RankChanged {
+                        from,
+                        to: rank,
+                        item: x.clone(),
+                    });
+                }
+                let stars = previous[from - 1].stars();
+                if stars != x.stars() {
+                    events.push(Event::StarsChanged {
+                        from: stars,
+                        to: x.stars(),
+                        item: x.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for (i, x) in previous.iter().enumerate() {
+        if let Some(key) = x.key() {
+            if !current_ranks.contains_key(key) {
+                events.push(Event::Left {
+                    rank: i + 1,
+                    item: x.clone(),
+                });
+            }
+        }
+    }
+
+    events
+}
+
+pub fn repos(builder: repos::Builder) -> Watcher<impl FnMut() -> Fetched<Repository>> {
+    Watcher::new(move || -> Fetched<Repository> {
+        let builder = builder.clone();
+        Box::pin(async move { builder.get_data().await })
+    })
+}
+
+pub fn developers(builder: developers::Builder) -> Watcher<impl FnMut() -> Fetched<Developer>> {
+    Watcher::new(move || -> Fetched<Developer> {
+        let builder = builder.clone();
+        Box::pin(builder.get_data())
+    })
+}